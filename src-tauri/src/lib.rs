@@ -1,21 +1,25 @@
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
     env,
     fs::{self},
-    io::{BufRead, BufReader, Write},
+    io::{BufRead, BufReader, Read, Write},
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr, TcpStream},
+    panic::{self, AssertUnwindSafe},
     path::{Path, PathBuf},
-    process::{Command, Stdio},
+    process::{Child, Command, ExitStatus, Stdio},
+    str::FromStr,
     sync::{
-        atomic::{AtomicBool, Ordering},
-        Arc, Mutex,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex, OnceLock, RwLock,
     },
     thread,
     time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 use tauri::{AppHandle, Emitter, Manager, State};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 struct ProcessConfig {
     name: String,
     command: String,
@@ -23,6 +27,376 @@ struct ProcessConfig {
     autostart: bool,
     #[serde(default)]
     autorestart: bool,
+    /// Directory to run in, relative to the project's working directory (or absolute), e.g.
+    /// `apps/web` or `../shared`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cwd: Option<String>,
+    /// Directories (relative to the process's working directory) prepended to `PATH`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    path_prepend: Vec<String>,
+    /// Activate the runtime pinned by `.nvmrc`/`.node-version` (fnm or nvm) or `.tool-versions`
+    /// (asdf) before running the command.
+    #[serde(default)]
+    respect_version_managers: bool,
+    /// `.env`-format files (relative to the working directory) loaded into the environment in
+    /// order, later files winning.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    env_from_file: Vec<String>,
+    /// Variables set for the process on every (re)start, over the inherited environment, the
+    /// project `env` and `env_from_file`. An empty value sets the variable to "", it doesn't
+    /// unset it.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    env: BTreeMap<String, String>,
+    /// Processes that must be running before `start_all_processes` starts this one.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    depends_on: Vec<String>,
+    /// Labels for starting/stopping related processes together.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    /// Annotate log lines with URL and file-path spans the UI can turn into links.
+    #[serde(default)]
+    detect_links: bool,
+    /// Tag log lines with their level and a color hint. Overrides the default colors per level
+    /// (`error: red`, `warn: yellow`); `{}` keeps the defaults. Off when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    level_colors: Option<BTreeMap<LogLevel, String>>,
+    /// Restart the process group when an output line matches, even if it hasn't exited.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    restart_on_log_regex: Option<String>,
+    /// Which stream `restart_on_log_regex` watches.
+    #[serde(default)]
+    restart_on_log_stream: LogStreamFilter,
+    /// Run periodically instead of continuously: `every 5m` or a cron expression.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    schedule: Option<String>,
+    /// Signal sent to the process group by `reload_process`, e.g. `HUP`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    reload_signal: Option<String>,
+    /// One-shot command run (in the process's cwd and env) by `reload_process`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    reload_command: Option<String>,
+    /// File mode creation mask as an octal string, e.g. `"077"` (Unix only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    umask: Option<String>,
+    /// Check run (in the process's cwd and env) every second after spawn; the process only
+    /// reports "running" once it exits 0.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ready_command: Option<String>,
+    /// How long `ready_command` may keep failing before the process is reported as crashed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ready_timeout_secs: Option<u64>,
+    /// TCP port the process listens on. Starting is refused while something else holds it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    port: Option<u16>,
+    /// Seconds after the first start during which "running" is held back (as "starting"), so
+    /// restarts during bootstrap don't make the status flicker. A `ready_command` takes
+    /// precedence.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    startup_grace_secs: Option<u64>,
+    /// Seconds to wait before each restart; 1 when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    restart_delay_secs: Option<u64>,
+    /// Autorestarts allowed after back-to-back crashes before giving up; unlimited when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_restarts: Option<u32>,
+    /// Seconds a stopping process gets after SIGTERM before SIGKILL; 3 when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    stop_timeout_secs: Option<u64>,
+    /// Signals sent in turn to stop the process, each followed by a wait, e.g. SIGINT, SIGTERM,
+    /// then SIGKILL. Replaces `stop_timeout_secs` when set; the last step must be `KILL`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    stop_sequence: Vec<StopStep>,
+    /// How `write_to_process` input reaches the program.
+    #[serde(default)]
+    stdin_mode: StdinMode,
+    /// Character encoding of the process's output; invalid bytes become U+FFFD.
+    #[serde(default)]
+    output_encoding: OutputEncoding,
+    /// Best-effort resource limits applied to the process tree (Unix only).
+    #[serde(default, skip_serializing_if = "ResourceLimits::is_empty")]
+    rlimits: ResourceLimits,
+    /// Set by `disable_process` (persisted in the app settings, not the config file); disabled
+    /// processes are skipped by autostart and refused by `start_process`.
+    #[serde(skip_deserializing, skip_serializing_if = "std::ops::Not::not")]
+    disabled: bool,
+    /// Write the session's output and input to `.myterm/recordings/` as an asciicast file.
+    #[serde(default)]
+    record: bool,
+    /// Run the command inside a Docker or Podman container instead of on the host.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    container: Option<ContainerConfig>,
+    /// The project's top-level `env`, copied in by `read_project_config`.
+    #[serde(skip)]
+    project_env: BTreeMap<String, String>,
+    /// The project's top-level `root` resolved against the config's directory, copied in by
+    /// `read_project_config`. Processes run here instead of the config's directory.
+    #[serde(skip)]
+    project_root: Option<PathBuf>,
+    /// This process's file in the project's `log_dir`, copied in by `read_project_config`.
+    #[serde(skip)]
+    log_file: Option<PathBuf>,
+    /// How that file is written, from the project's top-level log settings.
+    #[serde(skip)]
+    log_options: LogFileOptions,
+    /// The project's `strip_ansi`, copied in by `read_project_config`.
+    #[serde(skip)]
+    strip_ansi: bool,
+    /// Set by `run_once`: the process runs to completion instead of staying up.
+    #[serde(skip)]
+    run_once: bool,
+    /// Keys this version doesn't know; `lint_config` warns about them.
+    #[serde(flatten, skip_serializing)]
+    unknown: BTreeMap<String, serde_yaml::Value>,
+}
+
+impl ProcessConfig {
+    /// Tasks that finish on their own (`run_once` and scheduled processes), as opposed to
+    /// services. Independent of `autorestart`, which defaults to off for services too.
+    fn is_one_shot(&self) -> bool {
+        self.run_once || self.schedule.is_some()
+    }
+
+    /// Where this process runs, given the project's working directory.
+    fn dir_in(&self, working_dir: &Path) -> PathBuf {
+        match &self.cwd {
+            Some(cwd) => working_dir.join(cwd),
+            None => working_dir.to_path_buf(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+struct ResourceLimits {
+    /// Caps the address space (`RLIMIT_AS`), in MiB.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_memory_mb: Option<u64>,
+    /// Caps open file descriptors (`RLIMIT_NOFILE`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_open_files: Option<u64>,
+}
+
+impl ResourceLimits {
+    fn is_empty(&self) -> bool {
+        self.max_memory_mb.is_none() && self.max_open_files.is_none()
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.max_memory_mb == Some(0) {
+            return Err("rlimits.max_memory_mb must be greater than 0".to_string());
+        }
+        if self.max_open_files == Some(0) {
+            return Err("rlimits.max_open_files must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+
+    /// Runs in the forked child before exec; sets both the soft and hard limit so the command
+    /// can't raise them again.
+    #[cfg(unix)]
+    fn apply(&self) -> std::io::Result<()> {
+        let limit = |value: u64| libc::rlimit {
+            rlim_cur: value as libc::rlim_t,
+            rlim_max: value as libc::rlim_t,
+        };
+        if let Some(mb) = self.max_memory_mb {
+            let bytes = mb.saturating_mul(1024 * 1024);
+            if unsafe { libc::setrlimit(libc::RLIMIT_AS, &limit(bytes)) } != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+        }
+        if let Some(files) = self.max_open_files {
+            if unsafe { libc::setrlimit(libc::RLIMIT_NOFILE, &limit(files)) } != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parses an octal umask such as `022`, `0022` or `0o022`.
+fn parse_umask(value: &str) -> Result<u32, String> {
+    let trimmed = value.trim();
+    let digits = trimmed.strip_prefix("0o").unwrap_or(trimmed);
+    u32::from_str_radix(digits, 8)
+        .ok()
+        .filter(|mask| *mask <= 0o777)
+        .ok_or_else(|| format!("Invalid umask {:?}: expected octal such as 022", value))
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum LogStreamFilter {
+    Stdout,
+    #[default]
+    Stderr,
+    Any,
+}
+
+impl LogStreamFilter {
+    fn matches(self, stream: &str) -> bool {
+        match self {
+            LogStreamFilter::Stdout => stream == "stdout",
+            LogStreamFilter::Stderr => stream == "stderr",
+            LogStreamFilter::Any => true,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum StdinMode {
+    /// Write each input as-is.
+    #[default]
+    Raw,
+    /// Hold input back until it completes a line.
+    Line,
+    /// Write and flush one character at a time.
+    Char,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum LogFileMode {
+    /// Keep adding to the file across starts.
+    #[default]
+    Append,
+    /// Empty the file each time the process is started (autorestarts keep appending).
+    Truncate,
+}
+
+impl LogFileMode {
+    fn is_append(&self) -> bool {
+        *self == LogFileMode::Append
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum LogFormat {
+    /// One prefixed line of text per output line.
+    #[default]
+    Text,
+    /// One JSON object per output line, for other tools to read.
+    Jsonl,
+}
+
+impl LogFormat {
+    fn is_text(&self) -> bool {
+        *self == LogFormat::Text
+    }
+}
+
+/// The project's settings for its processes' log files.
+#[derive(Debug, Clone, Default)]
+struct LogFileOptions {
+    mode: LogFileMode,
+    format: LogFormat,
+    prefix_format: Option<String>,
+    max_bytes: Option<u64>,
+    compress: bool,
+}
+
+/// One step of a `stop_sequence`: send `signal` to the group, then give it `wait_secs` to exit.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct StopStep {
+    signal: String,
+    #[serde(default)]
+    wait_secs: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct ContainerConfig {
+    image: String,
+    /// Extra `host:container[:options]` volumes; relative host paths are resolved against the
+    /// project. The project itself is always mounted at `workdir`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    mounts: Vec<String>,
+    /// `docker` (default) or `podman`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    runtime: Option<String>,
+    /// Where the project is mounted and the command runs; `/workspace` by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    workdir: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    /// The first level keyword in the line (`ERROR`, `[warn]`, `level=info`, ...), ignoring
+    /// ANSI color codes.
+    fn detect(line: &str) -> Option<Self> {
+        static KEYWORD: OnceLock<Regex> = OnceLock::new();
+        static ANSI: OnceLock<Regex> = OnceLock::new();
+        let keyword = KEYWORD.get_or_init(|| {
+            Regex::new(r"(?i)\b(fatal|panic|error|err|warning|warn|info|debug|trace)\b")
+                .expect("valid level regex")
+        });
+        let ansi = ANSI.get_or_init(|| Regex::new(r"\x1b\[[0-9;]*m").expect("valid ansi regex"));
+        let plain = ansi.replace_all(line, "");
+        let found = keyword.find(&plain)?;
+        match found.as_str().to_ascii_lowercase().as_str() {
+            "fatal" | "panic" | "error" | "err" => Some(LogLevel::Error),
+            "warning" | "warn" => Some(LogLevel::Warn),
+            "info" => Some(LogLevel::Info),
+            "debug" => Some(LogLevel::Debug),
+            _ => Some(LogLevel::Trace),
+        }
+    }
+
+    fn default_color(self) -> Option<&'static str> {
+        match self {
+            LogLevel::Error => Some("red"),
+            LogLevel::Warn => Some("yellow"),
+            _ => None,
+        }
+    }
+}
+
+/// A WHATWG encoding label such as `utf-8`, `shift_jis` or `windows-1252`. Unknown labels are
+/// rejected when the config is loaded.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(try_from = "String", into = "String")]
+struct OutputEncoding(&'static encoding_rs::Encoding);
+
+impl Default for OutputEncoding {
+    fn default() -> Self {
+        OutputEncoding(encoding_rs::UTF_8)
+    }
+}
+
+impl TryFrom<String> for OutputEncoding {
+    type Error = String;
+
+    fn try_from(label: String) -> Result<Self, String> {
+        let encoding = encoding_rs::Encoding::for_label_no_replacement(label.trim().as_bytes())
+            .ok_or_else(|| format!("Unknown output_encoding {:?}", label))?;
+        // Output is split into lines on `\n` bytes, which UTF-16 doesn't keep intact.
+        if encoding == encoding_rs::UTF_16LE || encoding == encoding_rs::UTF_16BE {
+            return Err(format!("output_encoding {:?} isn't supported", label));
+        }
+        Ok(OutputEncoding(encoding))
+    }
+}
+
+impl From<OutputEncoding> for String {
+    fn from(encoding: OutputEncoding) -> String {
+        encoding.0.name().to_ascii_lowercase()
+    }
+}
+
+impl OutputEncoding {
+    fn decode(self, bytes: &[u8]) -> String {
+        // Lines are decoded one at a time, so a BOM can only be a stray one mid-stream.
+        self.0.decode_without_bom_handling(bytes).0.into_owned()
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -31,17 +405,216 @@ struct ActionConfig {
     command: String,
 }
 
+/// Config schema version this build understands. Bump it when a config written for the new
+/// version would be misread by older builds.
+const CONFIG_VERSION: u32 = 1;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct ProjectConfig {
+    /// Schema version the config was written for; a newer one than `CONFIG_VERSION` is refused.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    version: Option<u32>,
     name: String,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     actions: Vec<ActionConfig>,
     processes: Vec<ProcessConfig>,
+    /// Directory the processes run in, relative to the config's directory (or absolute), for
+    /// configs kept outside the project root such as in `config/`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    root: Option<String>,
+    /// Variables set for every process, below anything the process sets itself.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    env: BTreeMap<String, String>,
+    /// Directory, relative to the config's directory (or absolute), where each process's output
+    /// is also written to `<process>.log`. Off when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    log_dir: Option<String>,
+    /// What happens to an existing log file when its process is started.
+    #[serde(default, skip_serializing_if = "LogFileMode::is_append")]
+    log_file_mode: LogFileMode,
+    /// Put before each line in log files, with `{timestamp}`, `{process}` and `{stream}` filled
+    /// in. Unset means `[{stream}] `. The UI gets these as fields and never sees the prefix.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    log_prefix_format: Option<String>,
+    /// `jsonl` writes log files as JSON Lines records instead of text.
+    #[serde(default, skip_serializing_if = "LogFormat::is_text")]
+    log_format: LogFormat,
+    /// Once a log file reaches this size it's moved to `<process>.log.1` and a new one started.
+    /// Off when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    log_max_bytes: Option<u64>,
+    /// Gzip rotated log files (`<process>.log.1.gz`); the active file stays plain text.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    log_compress: bool,
+    /// Remove ANSI escape codes from output lines; the original goes along as `raw`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    strip_ansi: bool,
+    /// Keys this version doesn't know; `lint_config` warns about them.
+    #[serde(flatten, skip_serializing)]
+    unknown: BTreeMap<String, serde_yaml::Value>,
+}
+
+impl ProjectConfig {
+    /// Where processes run: `root` resolved against `config_dir`, or `config_dir` itself.
+    fn working_dir(&self, config_dir: &Path) -> PathBuf {
+        match &self.root {
+            Some(root) => config_dir.join(root),
+            None => config_dir.to_path_buf(),
+        }
+    }
+
+    /// Copies the project-wide `root` and `env` onto every process, so code that only sees a
+    /// `ProcessConfig` (like `plan_spawn`) still honours them.
+    fn apply_project_settings(&mut self, config_dir: &Path) {
+        let root = self.root.as_ref().map(|_| self.working_dir(config_dir));
+        let log_dir = self.log_dir.as_ref().map(|dir| config_dir.join(dir));
+        for process in &mut self.processes {
+            process.project_env = self.env.clone();
+            process.project_root = root.clone();
+            process.log_file = log_dir
+                .as_ref()
+                .map(|dir| dir.join(format!("{}.log", service_slug(&process.name))));
+            process.log_options = LogFileOptions {
+                mode: self.log_file_mode,
+                format: self.log_format,
+                prefix_format: self.log_prefix_format.clone(),
+                max_bytes: self.log_max_bytes,
+                compress: self.log_compress,
+            };
+            process.strip_ansi = self.strip_ansi;
+        }
+    }
+}
+
+/// Only the top-level `version` of a config, read before the full parse so a newer schema
+/// gets a clear error instead of whatever the full parse trips over.
+#[derive(Deserialize)]
+struct ConfigVersionOnly {
+    #[serde(default)]
+    version: Option<u32>,
+}
+
+fn check_config_version(normalized: &str) -> Result<(), String> {
+    let Ok(ConfigVersionOnly { version }) = serde_yaml::from_str(normalized) else {
+        // Let the full parse report the problem.
+        return Ok(());
+    };
+    match version {
+        Some(version) if version > CONFIG_VERSION => Err(format!(
+            "This config requires a newer MyTerm (config version {}, this build supports up to {})",
+            version, CONFIG_VERSION
+        )),
+        _ => Ok(()),
+    }
 }
 
 #[derive(Default, Clone)]
 struct ProcessManager {
     processes: Arc<Mutex<HashMap<String, ManagedProcess>>>,
+    /// Most recent exit per process key, kept after the process leaves `processes`.
+    last_exits: Arc<Mutex<HashMap<String, LastExit>>>,
+    /// Projects whose `.myterm/lock` this instance holds.
+    locked_projects: Arc<Mutex<HashSet<PathBuf>>>,
+    /// Filtered log subscriptions by id; see `subscribe_filtered_logs`.
+    log_subscriptions: Arc<Mutex<HashMap<u64, LogSubscription>>>,
+    next_subscription_id: Arc<AtomicU64>,
+    /// The last `LOG_BUFFER_LINES` output lines per process key, kept after the process exits
+    /// so views that mount later can backfill via `get_logs`.
+    log_buffers: Arc<Mutex<HashMap<String, VecDeque<LogEvent>>>>,
+}
+
+/// Output lines `get_logs` can return per process.
+const LOG_BUFFER_LINES: usize = 2000;
+
+/// Output lines of one process (by key) that match `include`, don't match `exclude` and, with
+/// `min_level`, carry a level at least that severe. Lines without a level don't pass `min_level`.
+struct LogSubscription {
+    key: String,
+    include: Option<Regex>,
+    exclude: Option<Regex>,
+    min_level: Option<LogLevel>,
+}
+
+impl LogSubscription {
+    fn matches(&self, event: &LogEvent) -> bool {
+        let line = &event.line;
+        self.include.as_ref().is_none_or(|re| re.is_match(line))
+            && !self.exclude.as_ref().is_some_and(|re| re.is_match(line))
+            && self.min_level.is_none_or(|min_level| {
+                // `level` is only filled in for processes with `level_colors`.
+                event
+                    .level
+                    .or_else(|| LogLevel::detect(line))
+                    .is_some_and(|level| level <= min_level)
+            })
+    }
+}
+
+/// App-wide preferences persisted as JSON in the app config dir.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+struct AppSettings {
+    /// Shell used to run processes and actions when `$SHELL` is not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    default_shell: Option<String>,
+    /// Cap on managed processes across all projects; unlimited when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_total_processes: Option<usize>,
+    /// Whether one-shot processes (`run_once` tasks and scheduled processes) count towards
+    /// `max_total_processes`.
+    #[serde(default)]
+    limit_counts_one_shot: bool,
+    /// Last-exit entries older than this are dropped when the UI syncs; kept forever when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_exit_max_age_secs: Option<u64>,
+    /// Process keys turned off with `disable_process`, until `enable_process`.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    disabled_processes: BTreeSet<String>,
+    /// Project folders registered with `add_project`, most recently added first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    projects: Vec<String>,
+}
+
+struct SettingsStore {
+    path: Option<PathBuf>,
+    settings: Mutex<AppSettings>,
+}
+
+impl SettingsStore {
+    fn load(path: Option<PathBuf>) -> Self {
+        let settings = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            settings: Mutex::new(settings),
+        }
+    }
+
+    fn get(&self) -> AppSettings {
+        self.settings
+            .lock()
+            .map(|settings| settings.clone())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, settings: AppSettings) -> Result<(), String> {
+        if let Some(path) = &self.path {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+            }
+            let json = serde_json::to_string_pretty(&settings).map_err(|err| err.to_string())?;
+            fs::write(path, json).map_err(|err| format!("{} ({})", err, path.display()))?;
+        }
+        let mut current = self
+            .settings
+            .lock()
+            .map_err(|_| "Settings poisoned".to_string())?;
+        *current = settings;
+        Ok(())
+    }
 }
 
 #[derive(Default)]
@@ -63,8 +636,147 @@ struct ManagedProcess {
     /// PID of the shell process we spawn. On Unix we also use this as the process group id (pgid)
     /// because we call `setpgid(0, 0)` in the child.
     pid: u32,
+    project_path: String,
+    process_name: SharedName,
+    /// Last status reported for this process ("starting" until the first spawn).
+    status: String,
+    /// Read by the supervisor after every exit, so `set_autorestart` applies to the next crash.
+    autorestart: Arc<AtomicBool>,
+    /// See `ProcessConfig::is_one_shot`. Fixed at spawn and kept apart from `autorestart`, so
+    /// `set_autorestart` doesn't change how the process counts against the process limit or
+    /// update preconditions.
+    one_shot: bool,
     stop_flag: Arc<AtomicBool>,
-    stdin: Arc<Mutex<Option<std::process::ChildStdin>>>,
+    /// `None` for processes without a restart loop (scheduled or attached).
+    restart: Option<RestartControl>,
+    stdin_mode: StdinMode,
+    stdin: Arc<Mutex<StdinPipe>>,
+    /// Set for `record: true` processes; shared with the log readers.
+    recording: Option<Arc<Recording>>,
+    /// Set for processes with a `container`, so stopping also stops the container.
+    container: Option<ContainerHandle>,
+    /// What was spawned; `None` for attached processes. Compared by `config_drift`.
+    plan: Option<SpawnPlan>,
+    /// When the current run reached "running"; `None` while it isn't.
+    started_at: Option<Instant>,
+    /// Autorestarts (and requested restarts) since `start_process`.
+    restart_count: u32,
+    /// Time between SIGTERM and SIGKILL when stopping or restarting, fixed at spawn so a config
+    /// edit doesn't change an in-flight shutdown.
+    stop_timeout: Duration,
+    /// The process's `stop_sequence`, fixed at spawn like `stop_timeout`; empty for the default.
+    stop_sequence: Vec<StopStep>,
+    /// The current run's Job Object, Windows' stand-in for the process group.
+    #[cfg(windows)]
+    job: Option<Arc<JobObject>>,
+}
+
+impl ManagedProcess {
+    /// Longest a stop can take before the group is killed outright.
+    fn stop_duration(&self) -> Duration {
+        if self.stop_sequence.is_empty() {
+            return self.stop_timeout;
+        }
+        let secs = self.stop_sequence.iter().map(|step| step.wait_secs).sum();
+        Duration::from_secs(secs)
+    }
+}
+
+/// Lets commands steer a supervisor's restart loop.
+#[derive(Clone, Default)]
+struct RestartControl {
+    /// Set before signalling the group so its exit counts as a requested restart, not a crash.
+    requested: Arc<AtomicBool>,
+    /// Cuts a pending autorestart wait short.
+    skip_wait: Arc<AtomicBool>,
+}
+
+/// The write end of a child's stdin plus input held back by `StdinMode::Line`.
+#[derive(Default)]
+struct StdinPipe {
+    pipe: Option<std::process::ChildStdin>,
+    pending: Vec<u8>,
+}
+
+impl StdinPipe {
+    fn write(&mut self, mode: StdinMode, input: &str) -> std::io::Result<()> {
+        let Some(pipe) = self.pipe.as_mut() else {
+            return Err(std::io::ErrorKind::NotConnected.into());
+        };
+        match mode {
+            StdinMode::Raw => pipe.write_all(input.as_bytes()).and_then(|_| pipe.flush()),
+            StdinMode::Char => input.chars().try_for_each(|ch| {
+                let mut buf = [0; 4];
+                pipe.write_all(ch.encode_utf8(&mut buf).as_bytes())?;
+                pipe.flush()
+            }),
+            StdinMode::Line => {
+                self.pending.extend_from_slice(input.as_bytes());
+                let Some(end) = self.pending.iter().rposition(|byte| *byte == b'\n') else {
+                    return Ok(());
+                };
+                let lines: Vec<u8> = self.pending.drain(..=end).collect();
+                pipe.write_all(&lines).and_then(|_| pipe.flush())
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ProcessSnapshot {
+    project_path: String,
+    process_name: String,
+    pid: u32,
+    status: String,
+    autorestart: bool,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct LastExit {
+    project_path: String,
+    process_name: String,
+    /// `None` when the process was terminated by a signal.
+    code: Option<i32>,
+    exited_at_ms: u128,
+}
+
+/// Per-process result of a bulk operation; one failure doesn't abort the rest.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProcessOutcome {
+    process_name: String,
+    error: Option<String>,
+}
+
+impl ProcessOutcome {
+    fn new(process_name: &str, result: Result<(), String>) -> Self {
+        Self {
+            process_name: process_name.to_string(),
+            error: result.err(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProcessUsage {
+    /// Managed services, including attached processes.
+    long_running: usize,
+    /// Managed `run_once` tasks and scheduled processes.
+    one_shot: usize,
+    /// How many of the above count against `limit`.
+    counted: usize,
+    limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncState {
+    processes: Vec<ProcessSnapshot>,
+    last_exits: Vec<LastExit>,
+    settings: AppSettings,
 }
 
 #[derive(Serialize, Clone)]
@@ -73,6 +785,36 @@ struct LogEvent {
     process_name: String,
     line: String,
     stream: String,
+    /// The line as the process wrote it, when the project's `strip_ansi` removed escape codes
+    /// from `line`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    raw: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    links: Vec<LogLink>,
+    /// Only set for processes with `level_colors`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    level: Option<LogLevel>,
+    /// Color hint for the line, e.g. `red`; unset means the default color.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    color: Option<String>,
+    /// Counts the spawns of a supervised process from 1, so output from different runs can be
+    /// told apart. Unset for attached processes and for messages not tied to a run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    run_id: Option<u64>,
+    /// Unix epoch millis when the line was read from the process (so bursts keep their
+    /// spacing), or when myterm emitted its own message.
+    timestamp_ms: u128,
+}
+
+/// A clickable span inside `LogEvent::line`; `start`/`end` are byte offsets.
+#[derive(Serialize, Clone)]
+struct LogLink {
+    start: usize,
+    end: usize,
+    /// "url" or "file".
+    kind: &'static str,
+    /// The URL, or the absolute file path (with any `:line:col` suffix kept).
+    target: String,
 }
 
 #[derive(Serialize, Clone)]
@@ -80,15 +822,103 @@ struct StatusEvent {
     project_path: String,
     process_name: String,
     status: String,
+    /// Which run of the process this is about; see `LogEvent::run_id`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    run_id: Option<u64>,
+    /// Unix epoch millis.
+    timestamp_ms: u128,
 }
 
-#[derive(Deserialize)]
+/// Sent when a process's declared `port` is already taken at start.
+#[derive(Serialize, Clone)]
+struct PortInUseEvent {
+    project_path: String,
+    process_name: String,
+    port: u16,
+    /// The listening process, when `lsof` can tell.
+    pid: Option<u32>,
+}
+
+/// Sent once when writing a process's recording or log file fails (e.g. a full disk); the file
+/// is closed and the log stream carries on.
+#[derive(Serialize, Clone)]
+struct LogWriteErrorEvent {
+    project_path: String,
+    process_name: String,
+    path: String,
+    error: String,
+}
+
+/// Sent when the supervisor starts waiting before relaunching a process.
+#[derive(Serialize, Clone)]
+struct RestartPendingEvent {
+    project_path: String,
+    process_name: String,
+    /// Seconds until the next attempt (rounded up).
+    seconds: u64,
+}
+
+#[derive(Deserialize, Clone)]
 struct GithubRelease {
     tag_name: String,
+    #[serde(default)]
+    published_at: Option<String>,
+    #[serde(default)]
+    prerelease: bool,
     assets: Vec<GithubAsset>,
 }
 
-#[derive(Deserialize)]
+impl GithubRelease {
+    fn zip_asset(&self) -> Option<&GithubAsset> {
+        self.assets.iter().find(|asset| asset.name == "MyTerm.zip")
+    }
+
+    /// The `.AppImage` asset, preferring one named for this machine's architecture when the
+    /// release ships several.
+    fn appimage_asset(&self) -> Option<&GithubAsset> {
+        let arch = match env::consts::ARCH {
+            "x86_64" => "amd64",
+            other => other,
+        };
+        let appimages: Vec<&GithubAsset> = self
+            .assets
+            .iter()
+            .filter(|asset| asset.name.ends_with(".AppImage"))
+            .collect();
+        appimages
+            .iter()
+            .find(|asset| asset.name.contains(arch) || asset.name.contains(env::consts::ARCH))
+            .or(appimages.first())
+            .copied()
+    }
+
+    /// What `install_update` installs on this platform: the AppImage on Linux, else the zip.
+    fn update_asset(&self) -> Option<&GithubAsset> {
+        if cfg!(target_os = "linux") {
+            self.appimage_asset()
+        } else {
+            self.zip_asset()
+        }
+    }
+
+    /// The `<asset>.sha256` published next to `asset`, if any.
+    fn checksum_url(&self, asset: &GithubAsset) -> Option<String> {
+        let name = format!("{}.sha256", asset.name);
+        self.assets
+            .iter()
+            .find(|candidate| candidate.name == name)
+            .map(|candidate| candidate.browser_download_url.clone())
+    }
+}
+
+/// Name of the update asset for this platform, for error messages.
+const UPDATE_ASSET_NAME: &str = if cfg!(target_os = "linux") {
+    "AppImage"
+} else {
+    "MyTerm.zip"
+};
+
+#[derive(Deserialize, Clone)]
 struct GithubAsset {
     name: String,
     browser_download_url: String,
@@ -100,6 +930,27 @@ struct UpdateInfo {
     available: bool,
     version: String,
     download_url: String,
+    /// The release's SHA-256 file for the download; pass it on to `install_update`. `None` when
+    /// the release has none, which the UI points out since the download then goes unverified.
+    checksum_url: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SelfUpdateSupport {
+    supported: bool,
+    /// Why updating in place isn't possible, when `supported` is false.
+    reason: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReleaseInfo {
+    version: String,
+    published_at: Option<String>,
+    prerelease: bool,
+    download_url: String,
+    checksum_url: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -109,917 +960,6845 @@ struct ConfigFilePayload {
     contents: String,
 }
 
-fn process_key(project_path: &str, process_name: &str) -> String {
-    format!("{}::{}", project_path, process_name)
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ConfigCandidates {
+    /// Every path checked, in resolution order.
+    candidates: Vec<String>,
+    /// The first candidate that exists, i.e. the one `load_project_config` would read.
+    existing: Option<String>,
+    /// Directory `existing` belongs to; an ancestor of the requested path when the config was
+    /// found by `search_parents`. Use it as the project path so cwds resolve correctly.
+    project_dir: Option<String>,
 }
 
-fn emit_status(app: &AppHandle, project_path: &str, process_name: &str, status: &str) {
-    let _ = app.emit(
-        "process-status",
-        StatusEvent {
-            project_path: project_path.to_string(),
-            process_name: process_name.to_string(),
-            status: status.to_string(),
-        },
-    );
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ServiceExport {
+    dir: String,
+    files: Vec<String>,
 }
 
-fn emit_log(app: &AppHandle, project_path: &str, process_name: &str, line: String, stream: &str) {
-    let _ = app.emit(
-        "process-log",
-        LogEvent {
-            project_path: project_path.to_string(),
-            process_name: process_name.to_string(),
-            line,
-            stream: stream.to_string(),
-        },
-    );
+fn process_key(project_path: &str, process_name: &str) -> String {
+    format!("{}::{}", project_path, process_name)
 }
 
-fn spawn_log_reader<R: std::io::Read + Send + 'static>(
-    app: AppHandle,
-    project_path: String,
-    process_name: String,
-    stream: &'static str,
-    reader: R,
-) {
-    thread::spawn(move || {
-        let buf = BufReader::new(reader);
-        for line in buf.lines().flatten() {
-            let _ = app.emit(
-                "process-log",
-                LogEvent {
-                    project_path: project_path.clone(),
-                    process_name: process_name.clone(),
-                    line,
-                    stream: stream.to_string(),
-                },
-            );
-        }
-    });
-}
+/// A running process's name, shared by its map entry, supervisor and log readers so
+/// `rename_process` can relabel it without a restart.
+#[derive(Clone)]
+struct SharedName(Arc<RwLock<String>>);
 
-fn config_path_candidates(project_path: &Path) -> Vec<PathBuf> {
-    vec![
-        project_path.join("myterm.yml"),
-        project_path.join("myterm.yaml"),
-    ]
-}
+impl SharedName {
+    fn new(name: String) -> Self {
+        Self(Arc::new(RwLock::new(name)))
+    }
 
-fn find_existing_config_path(project_path: &Path) -> Option<PathBuf> {
-    config_path_candidates(project_path)
-        .into_iter()
-        .find(|candidate| candidate.exists())
-}
+    fn get(&self) -> String {
+        self.0.read().map(|name| name.clone()).unwrap_or_default()
+    }
 
-fn read_project_config(project_path: &Path) -> Result<ProjectConfig, String> {
-    for candidate in config_path_candidates(project_path) {
-        match std::fs::read_to_string(&candidate) {
-            Ok(contents) => {
-                return serde_yaml::from_str(&contents)
-                    .map_err(|err| format!("{} ({})", err, candidate.display()));
-            }
-            Err(err) => {
-                if err.kind() == std::io::ErrorKind::NotFound {
-                    continue;
-                }
-                return Err(format!("{} ({})", err, candidate.display()));
-            }
+    fn set(&self, name: String) {
+        if let Ok(mut current) = self.0.write() {
+            *current = name;
         }
     }
-
-    Err("Missing myterm.yml".to_string())
 }
 
-#[cfg(unix)]
-fn signal_process_group(pgid: u32, signal: i32) {
-    if pgid == 0 {
-        return;
-    }
-
-    unsafe {
-        // Send to the entire process group to avoid orphaned children.
-        // Equivalent to: kill(-pgid, signal)
-        let _ = libc::kill(-(pgid as i32), signal);
-    }
+/// Where lifecycle and log events go. The desktop app forwards them to the webview; headless
+/// mode prints them to stdout instead.
+#[derive(Clone)]
+enum EventSink {
+    App(AppHandle),
+    Stdout,
 }
 
-#[cfg(unix)]
-fn process_group_exists(pgid: u32) -> bool {
-    if pgid == 0 {
-        return false;
+impl EventSink {
+    fn log(&self, event: LogEvent) {
+        match self {
+            EventSink::App(app) => {
+                let _ = app.emit("process-log", event);
+            }
+            EventSink::Stdout => println!("[{}] {}", event.process_name, event.line),
+        }
     }
 
-    unsafe {
-        let result = libc::kill(-(pgid as i32), 0);
-        if result == 0 {
-            return true;
+    fn status(&self, event: StatusEvent) {
+        match self {
+            EventSink::App(app) => {
+                let _ = app.emit("process-status", event);
+            }
+            EventSink::Stdout => println!("[{}] status: {}", event.process_name, event.status),
         }
-
-        let err = *libc::__error();
-        // ESRCH => no such process / group
-        err != libc::ESRCH
     }
-}
 
-fn stop_all_processes(manager: &ProcessManager) -> Vec<u32> {
-    let mut pgids = Vec::new();
-
-    if let Ok(map) = manager.processes.lock() {
-        for entry in map.values() {
-            entry.stop_flag.store(true, Ordering::SeqCst);
-            if entry.pid > 0 {
-                pgids.push(entry.pid);
+    fn port_in_use(&self, event: PortInUseEvent) {
+        match self {
+            EventSink::App(app) => {
+                let _ = app.emit("port-in-use", event);
             }
+            EventSink::Stdout => {}
         }
     }
 
-    #[cfg(unix)]
-    {
-        for pgid in &pgids {
-            signal_process_group(*pgid, libc::SIGTERM);
+    fn log_write_error(&self, event: LogWriteErrorEvent) {
+        match self {
+            EventSink::App(app) => {
+                let _ = app.emit("log-write-error", event);
+            }
+            EventSink::Stdout => eprintln!(
+                "[{}] stopped writing {}: {}",
+                event.process_name, event.path, event.error
+            ),
         }
     }
 
-    pgids
-}
-
-#[cfg(unix)]
-fn wait_then_force_kill(pgids: Vec<u32>, wait_for: Duration, hard_kill_after: Duration) {
-    let start = Instant::now();
-    // Give processes a moment to exit cleanly.
-    while start.elapsed() < wait_for {
-        if pgids.iter().all(|pgid| !process_group_exists(*pgid)) {
-            return;
+    fn restart_pending(&self, event: RestartPendingEvent) {
+        match self {
+            EventSink::App(app) => {
+                let _ = app.emit("restart-pending", event);
+            }
+            EventSink::Stdout => {
+                println!("[{}] restarting in {}s", event.process_name, event.seconds)
+            }
         }
-        thread::sleep(Duration::from_millis(50));
     }
 
-    // Still alive? Force kill.
-    for pgid in &pgids {
-        if process_group_exists(*pgid) {
-            signal_process_group(*pgid, libc::SIGKILL);
+    /// Delivers a line to one filtered subscription. Headless mode has no subscribers.
+    fn filtered_log(&self, subscription_id: u64, event: LogEvent) {
+        if let EventSink::App(app) = self {
+            let _ = app.emit(&format!("process-log-filtered:{}", subscription_id), event);
         }
     }
 
-    // Optionally wait a tiny bit more, but don't block too long on shutdown.
-    let start = Instant::now();
-    while start.elapsed() < hard_kill_after {
-        if pgids.iter().all(|pgid| !process_group_exists(*pgid)) {
-            return;
+    /// Reports processes whose group outlived SIGKILL during shutdown.
+    fn shutdown_stuck(&self, stuck: Vec<ProcessSnapshot>) {
+        match self {
+            EventSink::App(app) => {
+                let _ = app.emit("shutdown-stuck", stuck);
+            }
+            EventSink::Stdout => {
+                for process in stuck {
+                    eprintln!(
+                        "[{}] still running after SIGKILL (pgid {})",
+                        process.process_name, process.pid
+                    );
+                }
+            }
         }
-        thread::sleep(Duration::from_millis(50));
     }
 }
 
-fn detect_project_name(project_path: &Path) -> String {
-    project_path
-        .file_name()
-        .and_then(|name| name.to_str())
-        .unwrap_or("project")
-        .to_string()
+fn now_ms() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis())
+        .unwrap_or(0)
 }
 
-fn guess_processes(project_path: &Path) -> Vec<ProcessConfig> {
-    let procfile_path = project_path.join("Procfile");
-    if let Ok(contents) = std::fs::read_to_string(&procfile_path) {
-        let mut processes = Vec::new();
-        for line in contents.lines() {
-            let trimmed = line.trim();
-            if trimmed.is_empty() || trimmed.starts_with('#') {
-                continue;
-            }
-            let Some((name, cmd)) = trimmed.split_once(':') else {
-                continue;
-            };
-            let name = name.trim();
-            let cmd = cmd.trim();
-            if name.is_empty() || cmd.is_empty() {
-                continue;
-            }
-            processes.push(ProcessConfig {
-                name: name.to_string(),
-                command: cmd.to_string(),
-                autostart: false,
-                autorestart: true,
-            });
-        }
-        if !processes.is_empty() {
-            return processes;
-        }
+fn record_last_exit(manager: &ProcessManager, key: &str, exit: LastExit) {
+    if let Ok(mut exits) = manager.last_exits.lock() {
+        exits.insert(key.to_string(), exit);
     }
+}
 
-    let package_json_path = project_path.join("package.json");
-    if let Ok(contents) = std::fs::read_to_string(&package_json_path) {
-        if let Ok(json) = serde_json::from_str::<serde_json::Value>(&contents) {
-            let scripts = json.get("scripts");
-            let has_dev = scripts
-                .and_then(|s| s.get("dev"))
-                .and_then(|v| v.as_str())
-                .is_some();
-            let has_start = scripts
-                .and_then(|s| s.get("start"))
-                .and_then(|v| v.as_str())
-                .is_some();
+/// Drops last-exit entries for `project_path` (or every project), optionally only those older
+/// than `older_than`. Returns how many were removed.
+fn prune_last_exits(
+    manager: &ProcessManager,
+    project_path: Option<&str>,
+    older_than: Option<Duration>,
+) -> Result<usize, String> {
+    let cutoff = older_than.map(|age| now_ms().saturating_sub(age.as_millis()));
+    let mut exits = manager
+        .last_exits
+        .lock()
+        .map_err(|_| "Exit history poisoned".to_string())?;
+    let before = exits.len();
+    exits.retain(|_, exit| {
+        let in_scope = project_path.is_none_or(|project| project == exit.project_path);
+        let expired = cutoff.is_none_or(|cutoff| exit.exited_at_ms < cutoff);
+        !(in_scope && expired)
+    });
+    Ok(before - exits.len())
+}
 
-            if has_dev || has_start {
-                let pm = if project_path.join("pnpm-lock.yaml").exists() {
-                    "pnpm"
-                } else if project_path.join("yarn.lock").exists() {
-                    "yarn"
-                } else if project_path.join("bun.lockb").exists() {
-                    "bun"
-                } else {
-                    "npm"
-                };
+fn emit_status(
+    sink: &EventSink,
+    project_path: &str,
+    process_name: &str,
+    status: &str,
+    run_id: Option<u64>,
+) {
+    sink.status(StatusEvent {
+        project_path: project_path.to_string(),
+        process_name: process_name.to_string(),
+        status: status.to_string(),
+        run_id,
+        timestamp_ms: now_ms(),
+    });
+}
 
-                let script = if has_dev { "dev" } else { "start" };
+fn emit_log(
+    sink: &EventSink,
+    project_path: &str,
+    process_name: &str,
+    line: String,
+    stream: &str,
+    run_id: Option<u64>,
+) {
+    sink.log(LogEvent {
+        project_path: project_path.to_string(),
+        process_name: process_name.to_string(),
+        line,
+        stream: stream.to_string(),
+        raw: None,
+        links: Vec::new(),
+        level: None,
+        color: None,
+        run_id,
+        timestamp_ms: now_ms(),
+    });
+}
 
-                let cmd = match (pm, script) {
-                    ("yarn", "dev") => "yarn dev".to_string(),
-                    ("yarn", "start") => "yarn start".to_string(),
-                    ("pnpm", "dev") => "pnpm dev".to_string(),
-                    ("pnpm", "start") => "pnpm start".to_string(),
-                    ("bun", "dev") => "bun run dev".to_string(),
-                    ("bun", "start") => "bun run start".to_string(),
-                    (_, "dev") => "npm run dev".to_string(),
-                    (_, "start") => "npm start".to_string(),
-                    _ => "npm run dev".to_string(),
-                };
+/// Per-process context shared by the stdout and stderr reader threads.
+#[derive(Clone)]
+struct LogSource {
+    sink: EventSink,
+    manager: ProcessManager,
+    project_path: String,
+    process_name: SharedName,
+    detect_links: bool,
+    level_colors: Option<BTreeMap<LogLevel, String>>,
+    restart_trigger: Option<Arc<LogRestartTrigger>>,
+    recording: Option<Arc<Recording>>,
+    log_file: Option<Arc<ProcessLogFile>>,
+    encoding: OutputEncoding,
+    strip_ansi: bool,
+    /// The run whose pipes this source reads; set per spawn.
+    run_id: Option<u64>,
+}
 
-                return vec![ProcessConfig {
-                    name: script.to_string(),
-                    command: cmd,
-                    autostart: false,
-                    autorestart: true,
-                }];
+impl LogSource {
+    fn level_and_color(&self, line: &str) -> (Option<LogLevel>, Option<String>) {
+        let Some(colors) = &self.level_colors else {
+            return (None, None);
+        };
+        let Some(level) = LogLevel::detect(line) else {
+            return (None, None);
+        };
+        let color = match colors.get(&level) {
+            Some(color) => Some(color.clone()),
+            None => level.default_color().map(str::to_string),
+        };
+        (Some(level), color)
+    }
+
+    fn buffer(&self, event: &LogEvent) {
+        let Ok(mut buffers) = self.manager.log_buffers.lock() else {
+            return;
+        };
+        let key = process_key(&event.project_path, &event.process_name);
+        let buffer = buffers.entry(key).or_default();
+        if buffer.len() == LOG_BUFFER_LINES {
+            buffer.pop_front();
+        }
+        buffer.push_back(event.clone());
+    }
+
+    fn publish_filtered(&self, event: &LogEvent) {
+        let Ok(subscriptions) = self.manager.log_subscriptions.lock() else {
+            return;
+        };
+        if subscriptions.is_empty() {
+            return;
+        }
+        let key = process_key(&event.project_path, &event.process_name);
+        for (id, subscription) in subscriptions.iter() {
+            if subscription.key == key && subscription.matches(event) {
+                self.sink.filtered_log(*id, event.clone());
             }
         }
     }
+}
 
-    vec![ProcessConfig {
-        name: "dev".to_string(),
-        command: "echo 'Edit myterm.yml to add processes' && sleep 2".to_string(),
-        autostart: false,
-        autorestart: false,
-    }]
+fn recordings_dir(project_path: &Path) -> PathBuf {
+    project_path.join(".myterm").join("recordings")
 }
 
-fn parse_version(version: &str) -> Vec<u32> {
-    version
-        .trim()
-        .trim_start_matches('v')
-        .split('.')
-        .map(|part| part.parse::<u32>().unwrap_or(0))
-        .collect()
+/// First line of an asciicast v2 file.
+#[derive(Serialize)]
+struct CastHeader<'a> {
+    version: u32,
+    width: u32,
+    height: u32,
+    timestamp: u64,
+    title: &'a str,
 }
 
-fn is_newer_version(latest: &str, current: &str) -> bool {
-    let latest_parts = parse_version(latest);
-    let current_parts = parse_version(current);
-    let max_len = latest_parts.len().max(current_parts.len());
+/// An asciicast v2 session file for a `record: true` process: a JSON header line, then one
+/// `[seconds, "o" | "i", data]` line per chunk of output or input. Output comes from pipes
+/// rather than a terminal, so the header advertises a fixed 80x24 size.
+struct Recording {
+    path: PathBuf,
+    started: Instant,
+    /// `None` once `stop_recording` closed the file.
+    file: Mutex<Option<Box<dyn Write + Send>>>,
+}
 
-    for idx in 0..max_len {
-        let latest_value = *latest_parts.get(idx).unwrap_or(&0);
-        let current_value = *current_parts.get(idx).unwrap_or(&0);
-        if latest_value > current_value {
-            return true;
-        }
-        if latest_value < current_value {
-            return false;
+impl Recording {
+    fn create(project_path: &Path, process_name: &str) -> Result<Self, String> {
+        let dir = recordings_dir(project_path);
+        fs::create_dir_all(&dir).map_err(|err| format!("{} ({})", err, dir.display()))?;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0);
+        let file_name = format!("{}-{}.cast", service_slug(process_name), timestamp);
+        let path = dir.join(file_name);
+        let mut file =
+            fs::File::create(&path).map_err(|err| format!("{} ({})", err, path.display()))?;
+        let header = CastHeader {
+            version: 2,
+            width: 80,
+            height: 24,
+            timestamp,
+            title: process_name,
+        };
+        let header = serde_json::to_string(&header).map_err(|err| err.to_string())?;
+        writeln!(file, "{}", header).map_err(|err| err.to_string())?;
+        Ok(Self {
+            path,
+            started: Instant::now(),
+            file: Mutex::new(Some(Box::new(file))),
+        })
+    }
+
+    /// Appends one event; `code` is "o" for output and "i" for input. Bytes that aren't valid
+    /// UTF-8 are replaced, since asciicast data is a JSON string.
+    ///
+    /// A failed write closes the file, so only the first failure is returned.
+    fn write(&self, code: &str, data: &[u8]) -> std::io::Result<()> {
+        let Ok(mut file) = self.file.lock() else {
+            return Ok(());
+        };
+        let Some(handle) = file.as_mut() else {
+            return Ok(());
+        };
+        // Timed under the lock so events from stdout and stderr stay in order.
+        let event = (
+            self.started.elapsed().as_secs_f64(),
+            code,
+            String::from_utf8_lossy(data),
+        );
+        let Ok(line) = serde_json::to_string(&event) else {
+            return Ok(());
+        };
+        let result = writeln!(handle, "{}", line);
+        if result.is_err() {
+            // Disk full or similar; stop rather than leave a file with gaps.
+            *file = None;
         }
+        result
     }
 
-    false
-}
+    fn report_failure(
+        &self,
+        sink: &EventSink,
+        project_path: &str,
+        name: &str,
+        err: std::io::Error,
+    ) {
+        report_log_write_failure(sink, project_path, name, &self.path, err);
+    }
 
-fn is_backup_bundle(path: &Path) -> bool {
-    path.extension().and_then(|ext| ext.to_str()) == Some("old")
-        && path
-            .file_name()
-            .and_then(|name| name.to_str())
-            .map(|name| name.ends_with(".app.old"))
+    /// Closes the file. Returns false when it was already closed.
+    fn stop(&self) -> bool {
+        self.file
+            .lock()
+            .map(|mut file| file.take().is_some())
             .unwrap_or(false)
-}
-
-fn find_app_bundle_path() -> Result<PathBuf, String> {
-    let exe_path = env::current_exe().map_err(|err| err.to_string())?;
-    for ancestor in exe_path.ancestors() {
-        if ancestor.extension().and_then(|ext| ext.to_str()) == Some("app") {
-            return Ok(ancestor.to_path_buf());
-        }
-        if is_backup_bundle(ancestor) {
-            return Ok(ancestor.to_path_buf());
-        }
     }
+}
 
-    Err("Could not determine app bundle path".to_string())
+fn report_log_write_failure(
+    sink: &EventSink,
+    project_path: &str,
+    name: &str,
+    path: &Path,
+    err: std::io::Error,
+) {
+    sink.log_write_error(LogWriteErrorEvent {
+        project_path: project_path.to_string(),
+        process_name: name.to_string(),
+        path: path.to_string_lossy().to_string(),
+        error: err.to_string(),
+    });
 }
 
-fn resolve_primary_app_bundle_path(running_bundle: &Path) -> PathBuf {
-    if is_backup_bundle(running_bundle) {
-        running_bundle.with_extension("app")
-    } else {
-        running_bundle.to_path_buf()
-    }
+/// A process's file in the project's `log_dir`, shared by its stdout and stderr readers. Each
+/// line goes out in one write under the lock, so the two streams never interleave mid-line.
+struct ProcessLogFile {
+    path: PathBuf,
+    /// `None` after a failed write.
+    file: Mutex<Option<Box<dyn Write + Send>>>,
+    /// Bytes in the active file; only changed under the `file` lock.
+    size: AtomicU64,
+    /// See `ProjectConfig::log_max_bytes`.
+    max_bytes: Option<u64>,
+    compress: bool,
+    format: LogFormat,
+    /// See `ProjectConfig::log_prefix_format`.
+    prefix_format: Option<String>,
+    /// Lines written since the file was opened; numbers `jsonl` records.
+    seq: AtomicU64,
 }
 
-fn create_temp_dir() -> Result<PathBuf, String> {
-    let stamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map_err(|err| err.to_string())?
-        .as_millis();
-    let dir = env::temp_dir().join(format!("myterm-update-{}", stamp));
-    fs::create_dir_all(&dir).map_err(|err| err.to_string())?;
-    Ok(dir)
+/// A `log_format: jsonl` line.
+#[derive(Serialize)]
+struct LogRecord<'a> {
+    /// Unix epoch millis, as `LogEvent::timestamp_ms`.
+    ts: u128,
+    process: &'a str,
+    stream: &'a str,
+    line: &'a str,
+    seq: u64,
 }
 
-fn find_app_in_dir(root: &Path) -> Option<PathBuf> {
-    let entries = fs::read_dir(root).ok()?;
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if path.is_dir() {
-            if path.extension().and_then(|ext| ext.to_str()) == Some("app") {
-                return Some(path);
+impl ProcessLogFile {
+    fn open(path: &Path, settings: &LogFileOptions) -> Result<Self, String> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|err| format!("{} ({})", err, dir.display()))?;
+        }
+        let mut options = fs::OpenOptions::new();
+        match settings.mode {
+            LogFileMode::Append => options.create(true).append(true),
+            LogFileMode::Truncate => options.create(true).write(true).truncate(true),
+        };
+        let file = options
+            .open(path)
+            .map_err(|err| format!("{} ({})", err, path.display()))?;
+        let size = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+        Ok(Self {
+            path: path.to_path_buf(),
+            file: Mutex::new(Some(Box::new(file))),
+            size: AtomicU64::new(size),
+            max_bytes: settings.max_bytes,
+            compress: settings.compress,
+            format: settings.format,
+            prefix_format: settings.prefix_format.clone(),
+            seq: AtomicU64::new(0),
+        })
+    }
+
+    /// Appends the event's line after its prefix, or as a JSON record, rotating the file once it
+    /// reaches `max_bytes`. A failed write or rotation closes the file, so only the first
+    /// failure is returned.
+    fn write_line(&self, event: &LogEvent) -> std::io::Result<()> {
+        let Ok(mut file) = self.file.lock() else {
+            return Ok(());
+        };
+        let Some(handle) = file.as_mut() else {
+            return Ok(());
+        };
+        // Numbered under the lock so `seq` follows the order in the file.
+        let text = match self.format {
+            LogFormat::Text => {
+                let prefix = match &self.prefix_format {
+                    Some(format) => format_log_prefix(format, event),
+                    None => format!("[{}] ", event.stream),
+                };
+                format!("{}{}", prefix, event.line)
             }
-            if let Some(found) = find_app_in_dir(&path) {
-                return Some(found);
+            LogFormat::Jsonl => {
+                let record = LogRecord {
+                    ts: event.timestamp_ms,
+                    process: &event.process_name,
+                    stream: &event.stream,
+                    line: &event.line,
+                    seq: self.seq.fetch_add(1, Ordering::SeqCst),
+                };
+                serde_json::to_string(&record).map_err(std::io::Error::other)?
+            }
+        };
+        let line = format!("{}\n", text);
+        let result = handle.write_all(line.as_bytes()).and_then(|()| {
+            let size = self.size.fetch_add(line.len() as u64, Ordering::SeqCst) + line.len() as u64;
+            if self.max_bytes.is_none_or(|max| size < max) {
+                return Ok(());
             }
+            // Close the active file before moving it. Compressing happens here too, holding up
+            // this process's output for as long as that takes.
+            *file = None;
+            rotate_log_file(&self.path, self.compress)?;
+            *file = Some(Box::new(fs::File::create(&self.path)?));
+            self.size.store(0, Ordering::SeqCst);
+            Ok(())
+        });
+        if result.is_err() {
+            *file = None;
         }
+        result
     }
-    None
 }
 
-#[tauri::command(rename_all = "camelCase")]
-fn load_project_config(path: String) -> Result<ProjectConfig, String> {
-    let project_path = Path::new(&path);
-    read_project_config(project_path)
+/// Rotated segments kept per log file: `<process>.log.1` (the newest) to `.log.5`.
+const LOG_SEGMENTS_KEPT: usize = 5;
+
+/// `<process>.log.<index>`, plus `.gz` when `compressed`.
+fn log_segment_path(path: &Path, index: usize, compressed: bool) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", index));
+    if compressed {
+        name.push(".gz");
+    }
+    PathBuf::from(name)
 }
 
-#[tauri::command(rename_all = "camelCase")]
-fn init_project_config(path: String) -> Result<ProjectConfig, String> {
-    let project_path = Path::new(&path);
-    let config_path = project_path.join("myterm.yml");
-    if config_path.exists() {
-        return Err(format!("Config already exists at {}", config_path.display()));
+/// Shifts each `<process>.log.N[.gz]` to `N + 1`, dropping the oldest, then moves the active
+/// file to `.1`, gzipped when `compress`. Both suffixes are handled so toggling
+/// `log_compress` keeps the older segments in order.
+fn rotate_log_file(path: &Path, compress: bool) -> std::io::Result<()> {
+    for compressed in [false, true] {
+        match fs::remove_file(log_segment_path(path, LOG_SEGMENTS_KEPT, compressed)) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err),
+            _ => {}
+        }
+    }
+    for index in (1..LOG_SEGMENTS_KEPT).rev() {
+        for compressed in [false, true] {
+            let segment = log_segment_path(path, index, compressed);
+            if segment.exists() {
+                fs::rename(&segment, log_segment_path(path, index + 1, compressed))?;
+            }
+        }
     }
 
-    let config = ProjectConfig {
-        name: detect_project_name(project_path),
-        actions: Vec::new(),
-        processes: guess_processes(project_path),
-    };
+    let newest = log_segment_path(path, 1, false);
+    fs::rename(path, &newest)?;
+    if compress {
+        let mut plain = fs::File::open(&newest)?;
+        let gzipped = fs::File::create(log_segment_path(path, 1, true))?;
+        let mut encoder = flate2::write::GzEncoder::new(gzipped, flate2::Compression::default());
+        std::io::copy(&mut plain, &mut encoder)?;
+        encoder.finish()?;
+        fs::remove_file(&newest)?;
+    }
+    Ok(())
+}
 
-    let yaml = serde_yaml::to_string(&config).map_err(|err| err.to_string())?;
-    std::fs::write(&config_path, yaml)
-        .map_err(|err| format!("{} ({})", err, config_path.display()))?;
+/// A log file's segments that exist, oldest first and ending with the active file.
+fn log_segments(path: &Path) -> Vec<PathBuf> {
+    let mut segments: Vec<PathBuf> = (1..=LOG_SEGMENTS_KEPT)
+        .rev()
+        .filter_map(|index| {
+            [true, false]
+                .into_iter()
+                .map(|compressed| log_segment_path(path, index, compressed))
+                .find(|segment| segment.exists())
+        })
+        .collect();
+    if path.exists() {
+        segments.push(path.to_path_buf());
+    }
+    segments
+}
 
-    Ok(config)
+/// The lines of one segment, decompressing `.gz` ones.
+fn read_log_segment(path: &Path) -> Result<Vec<String>, String> {
+    let file = fs::File::open(path).map_err(|err| format!("{} ({})", err, path.display()))?;
+    let reader: Box<dyn Read> = if path.extension().is_some_and(|ext| ext == "gz") {
+        Box::new(flate2::read::GzDecoder::new(file))
+    } else {
+        Box::new(file)
+    };
+    let mut reader = BufReader::new(reader);
+    let mut lines = Vec::new();
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) => return Ok(lines),
+            Ok(_) => {
+                if line.ends_with(b"\n") {
+                    line.pop();
+                }
+                lines.push(String::from_utf8_lossy(&line).into_owned());
+            }
+            Err(err) => return Err(format!("{} ({})", err, path.display())),
+        }
+    }
 }
 
-#[tauri::command(rename_all = "camelCase")]
-fn read_project_config_file(path: String) -> Result<ConfigFilePayload, String> {
-    let project_path = Path::new(&path);
-    let config_path = find_existing_config_path(project_path)
-        .ok_or_else(|| "Missing myterm.yml".to_string())?;
-    let contents = fs::read_to_string(&config_path)
-        .map_err(|err| format!("{} ({})", err, config_path.display()))?;
-    Ok(ConfigFilePayload {
-        path: config_path.to_string_lossy().to_string(),
-        contents,
-    })
+/// Fills in `log_prefix_format`. The process name goes in last so braces in it are kept as is.
+fn format_log_prefix(format: &str, event: &LogEvent) -> String {
+    let timestamp = i64::try_from(event.timestamp_ms)
+        .ok()
+        .and_then(chrono::DateTime::from_timestamp_millis)
+        .map(|time| {
+            time.with_timezone(&chrono::Local)
+                .to_rfc3339_opts(chrono::SecondsFormat::Millis, false)
+        })
+        .unwrap_or_default();
+    format
+        .replace("{stream}", &event.stream)
+        .replace("{timestamp}", &timestamp)
+        .replace("{process}", &event.process_name)
 }
 
-#[tauri::command(rename_all = "camelCase")]
-fn write_project_config_file(path: String, contents: String) -> Result<(), String> {
-    let project_path = Path::new(&path);
-    let config_path = find_existing_config_path(project_path)
-        .unwrap_or_else(|| project_path.join("myterm.yml"));
+/// Minimum time between two log-triggered restarts of the same process.
+const LOG_RESTART_DEBOUNCE: Duration = Duration::from_secs(10);
 
-    fs::write(&config_path, contents)
-        .map_err(|err| format!("{} ({})", err, config_path.display()))?;
-    Ok(())
+/// Restarts a process when its output matches `restart_on_log_regex`.
+struct LogRestartTrigger {
+    regex: Regex,
+    streams: LogStreamFilter,
+    manager: ProcessManager,
+    project_path: String,
+    process_name: SharedName,
+    last_fired: Mutex<Option<Instant>>,
 }
 
-#[tauri::command(rename_all = "camelCase")]
-fn start_process(
-    app: AppHandle,
-    state: State<ProcessManager>,
-    project_path: String,
-    process_name: String,
-    command: String,
-    autorestart: bool,
-) -> Result<(), String> {
-    let key = process_key(&project_path, &process_name);
-    let manager = state.inner().clone();
+impl LogRestartTrigger {
+    /// Returns true when this line fired a restart.
+    fn check(&self, stream: &str, line: &str) -> bool {
+        if !self.streams.matches(stream) || !self.regex.is_match(line) {
+            return false;
+        }
 
-    {
-        let mut map = manager
-            .processes
-            .lock()
-            .map_err(|_| "Process map poisoned".to_string())?;
-        if map.contains_key(&key) {
-            return Err("Process already running".to_string());
+        let Ok(mut last_fired) = self.last_fired.lock() else {
+            return false;
+        };
+        if last_fired.is_some_and(|at| at.elapsed() < LOG_RESTART_DEBOUNCE) {
+            return false;
         }
-        map.insert(
-            key.clone(),
-            ManagedProcess {
-                pid: 0,
-                stop_flag: Arc::new(AtomicBool::new(false)),
-                stdin: Arc::new(Mutex::new(None)),
-            },
-        );
+        // Same path as `restart_process`, so a hung process still gets escalated to a kill.
+        let key = process_key(&self.project_path, &self.process_name.get());
+        if restart_managed_process(&self.manager, &key, false).is_err() {
+            return false;
+        }
+        *last_fired = Some(Instant::now());
+        true
     }
+}
 
-    let app_handle = app.clone();
-    thread::spawn(move || {
-        let stop_flag = {
-            let map = manager.processes.lock().ok();
-            map.and_then(|map| map.get(&key).map(|p| p.stop_flag.clone()))
-        };
+fn url_regex() -> &'static Regex {
+    static URL: OnceLock<Regex> = OnceLock::new();
+    URL.get_or_init(|| Regex::new(r#"https?://[^\s<>"'`]+"#).expect("valid url regex"))
+}
 
-        let Some(stop_flag) = stop_flag else {
-            return;
-        };
+fn file_regex() -> &'static Regex {
+    static FILE: OnceLock<Regex> = OnceLock::new();
+    FILE.get_or_init(|| {
+        Regex::new(r"(?:\.{1,2}/|/)?(?:[\w@.-]+/)*[\w@-][\w@.-]*\.[A-Za-z0-9]+(?::\d+){0,2}")
+            .expect("valid file regex")
+    })
+}
 
-        loop {
-            if stop_flag.load(Ordering::SeqCst) {
-                break;
+/// Finds URLs plus paths (optionally `:line:col`) that exist relative to `project_path`.
+fn detect_links(line: &str, project_path: &Path) -> Vec<LogLink> {
+    let mut links: Vec<LogLink> = url_regex()
+        .find_iter(line)
+        .map(|found| {
+            let url = found
+                .as_str()
+                .trim_end_matches(['.', ',', ';', ':', ')', ']']);
+            LogLink {
+                start: found.start(),
+                end: found.start() + url.len(),
+                kind: "url",
+                target: url.to_string(),
             }
+        })
+        .collect();
 
-            let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/zsh".to_string());
-            let mut cmd = Command::new(&shell);
-            cmd.arg("-ilc")
-                .arg(&command)
-                .current_dir(&project_path)
-                .stdin(Stdio::piped())
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped());
-
-            #[cfg(unix)]
-            {
-                use std::os::unix::process::CommandExt;
-                unsafe {
-                    cmd.pre_exec(|| {
-                        // Put the shell in its own process group so we can stop the entire tree.
-                        // (setpgid(0, 0) => pgid = pid)
-                        libc::setpgid(0, 0);
-                        Ok(())
-                    });
-                }
-            }
+    for found in file_regex().find_iter(line) {
+        if links
+            .iter()
+            .any(|link| found.start() < link.end && link.start < found.end())
+        {
+            continue;
+        }
+        let text = found.as_str();
+        let file = text.split(':').next().unwrap_or(text);
+        let resolved = project_path.join(file);
+        if !resolved.is_file() {
+            continue;
+        }
+        links.push(LogLink {
+            start: found.start(),
+            end: found.end(),
+            kind: "file",
+            target: format!("{}{}", resolved.display(), &text[file.len()..]),
+        });
+    }
 
-            let mut child = match cmd.spawn() {
-                Ok(child) => child,
-                Err(err) => {
-                    emit_log(
-                        &app_handle,
-                        &project_path,
-                        &process_name,
-                        format!("Failed to start: {}", err),
-                        "stderr",
-                    );
-                    emit_status(&app_handle, &project_path, &process_name, "crashed");
+    links.sort_by_key(|link| link.start);
+    links
+}
 
-                    if !autorestart {
+/// Removes ANSI escape sequences: CSI (colors, cursor movement), OSC such as window titles
+/// (`ESC ] 0;title BEL`, or ended by `ESC \`), the other string sequences (DCS, SOS, PM, APC)
+/// and short escapes like `ESC ( B`. Lines are read whole, so a sequence is only cut short when
+/// it spans a newline; the unterminated part is dropped up to the end of the line.
+fn strip_ansi(line: &str) -> String {
+    let mut plain = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch != '\x1b' {
+            plain.push(ch);
+            continue;
+        }
+        match chars.next() {
+            // Parameter and intermediate bytes, then a final byte in `@`..=`~`.
+            Some('[') => {
+                for ch in chars.by_ref() {
+                    if ('@'..='~').contains(&ch) {
                         break;
                     }
-
-                    thread::sleep(Duration::from_secs(1));
-                    continue;
                 }
-            };
-
-            let pid = child.id();
-            if let Ok(mut map) = manager.processes.lock() {
-                if let Some(entry) = map.get_mut(&key) {
-                    entry.pid = pid;
-                    if let Some(stdin) = child.stdin.take() {
-                        if let Ok(mut handle) = entry.stdin.lock() {
-                            *handle = Some(stdin);
-                        }
+            }
+            // Runs to BEL or ST (`ESC \`).
+            Some(']' | 'P' | 'X' | '^' | '_') => {
+                while let Some(ch) = chars.next() {
+                    if ch == '\x07' {
+                        break;
+                    }
+                    if ch == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
                     }
                 }
             }
-
-            emit_status(&app_handle, &project_path, &process_name, "running");
-
-            if let Some(stdout) = child.stdout.take() {
-                spawn_log_reader(
-                    app_handle.clone(),
-                    project_path.clone(),
-                    process_name.clone(),
-                    "stdout",
-                    stdout,
-                );
-            }
-
-            if let Some(stderr) = child.stderr.take() {
-                spawn_log_reader(
-                    app_handle.clone(),
-                    project_path.clone(),
-                    process_name.clone(),
-                    "stderr",
-                    stderr,
-                );
-            }
-
-            let status = child.wait();
-
-            if let Ok(mut map) = manager.processes.lock() {
-                if let Some(entry) = map.get_mut(&key) {
-                    if let Ok(mut handle) = entry.stdin.lock() {
-                        *handle = None;
+            // Intermediate bytes, then one final byte.
+            Some(' '..='/') => {
+                for ch in chars.by_ref() {
+                    if !(' '..='/').contains(&ch) {
+                        break;
                     }
                 }
             }
+            // Two-character escapes such as `ESC 7` or `ESC =`.
+            _ => {}
+        }
+    }
+    plain
+}
 
-            match status {
-                Ok(status) => {
-                    if let Some(code) = status.code() {
-                        emit_log(
-                            &app_handle,
-                            &project_path,
-                            &process_name,
-                            format!("[exit] code {}", code),
-                            "stdout",
-                        );
-                    } else {
-                        emit_log(
-                            &app_handle,
-                            &project_path,
-                            &process_name,
-                            "[exit] terminated by signal".to_string(),
-                            "stdout",
-                        );
+/// Reads `reader` line by line into logs on its own thread, which finishes at end of stream.
+fn spawn_log_reader<R: std::io::Read + Send + 'static>(
+    source: LogSource,
+    stream: &'static str,
+    reader: R,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let mut buf = BufReader::new(reader);
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+            // Read raw bytes so a non-UTF-8 line doesn't end the loop the way `lines()` would.
+            let mut line = Vec::new();
+            loop {
+                line.clear();
+                match buf.read_until(b'\n', &mut line) {
+                    Ok(0) => break,
+                    Ok(_) => {
+                        let timestamp_ms = now_ms();
+                        if let Some(recording) = &source.recording {
+                            if let Err(err) = recording.write("o", &line) {
+                                let name = source.process_name.get();
+                                recording.report_failure(
+                                    &source.sink,
+                                    &source.project_path,
+                                    &name,
+                                    err,
+                                );
+                            }
+                        }
+                        if line.ends_with(b"\n") {
+                            line.pop();
+                            if line.ends_with(b"\r") {
+                                line.pop();
+                            }
+                        }
+                        let decoded = source.encoding.decode(&line);
+                        let (text, raw) = if source.strip_ansi {
+                            let stripped = strip_ansi(&decoded);
+                            let changed = stripped != decoded;
+                            (stripped, changed.then_some(decoded))
+                        } else {
+                            (decoded, None)
+                        };
+                        let links = if source.detect_links {
+                            detect_links(&text, Path::new(&source.project_path))
+                        } else {
+                            Vec::new()
+                        };
+                        let (level, color) = source.level_and_color(&text);
+                        let restart = source
+                            .restart_trigger
+                            .as_ref()
+                            .is_some_and(|trigger| trigger.check(stream, &text));
+                        let event = LogEvent {
+                            project_path: source.project_path.clone(),
+                            process_name: source.process_name.get(),
+                            line: text,
+                            stream: stream.to_string(),
+                            raw,
+                            links,
+                            level,
+                            color,
+                            run_id: source.run_id,
+                            timestamp_ms,
+                        };
+                        if let Some(log_file) = &source.log_file {
+                            if let Err(err) = log_file.write_line(&event) {
+                                report_log_write_failure(
+                                    &source.sink,
+                                    &source.project_path,
+                                    &event.process_name,
+                                    &log_file.path,
+                                    err,
+                                );
+                            }
+                        }
+                        source.buffer(&event);
+                        source.publish_filtered(&event);
+                        source.sink.log(event);
+                        if restart {
+                            emit_log(
+                                &source.sink,
+                                &source.project_path,
+                                &source.process_name.get(),
+                                "[restart] output matched restart_on_log_regex".to_string(),
+                                "system",
+                                source.run_id,
+                            );
+                        }
                     }
+                    Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                    Err(_) => break,
                 }
-                Err(err) => {
-                    emit_log(
-                        &app_handle,
-                        &project_path,
-                        &process_name,
-                        format!("[exit] wait failed: {}", err),
-                        "stderr",
-                    );
-                }
-            }
-
-            if stop_flag.load(Ordering::SeqCst) {
-                emit_status(&app_handle, &project_path, &process_name, "stopped");
-                break;
             }
+        }));
 
-            emit_status(&app_handle, &project_path, &process_name, "crashed");
-
-            if !autorestart {
-                break;
-            }
+        // Whatever happened above, keep the pipe drained so the child can't block on a full
+        // buffer and leave `wait()` hanging.
+        let _ = std::io::copy(&mut buf, &mut std::io::sink());
+    })
+}
 
-            thread::sleep(Duration::from_secs(1));
-        }
+/// How long `run_child` waits after an exit for the log readers to reach the end of the pipes.
+/// A background process the child left behind can hold them open indefinitely.
+const LOG_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
 
-        if let Ok(mut map) = manager.processes.lock() {
-            map.remove(&key);
+/// Waits up to `LOG_DRAIN_TIMEOUT` for `readers` to finish, so a run's last lines are logged
+/// before its exit is.
+fn drain_log_readers(readers: Vec<thread::JoinHandle<()>>) {
+    let deadline = Instant::now() + LOG_DRAIN_TIMEOUT;
+    while readers.iter().any(|reader| !reader.is_finished()) && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(10));
+    }
+    for reader in readers {
+        if reader.is_finished() {
+            let _ = reader.join();
         }
-    });
-
-    Ok(())
+    }
 }
 
-#[tauri::command(rename_all = "camelCase")]
-fn stop_process(
-    state: State<ProcessManager>,
-    project_path: String,
-    process_name: String,
-) -> Result<(), String> {
-    let key = process_key(&project_path, &process_name);
-    let manager = state.inner().clone();
-
-    let (pid, stop_flag) = {
-        let map = manager
-            .processes
-            .lock()
-            .map_err(|_| "Process map poisoned".to_string())?;
-        let Some(entry) = map.get(&key) else {
-            return Err("Process not running".to_string());
-        };
-        (entry.pid, entry.stop_flag.clone())
-    };
-
-    stop_flag.store(true, Ordering::SeqCst);
+/// How long a process group may be gone while `try_wait` still reports the child alive before
+/// the watchdog gives up on it.
+#[cfg(unix)]
+const WAIT_WATCHDOG_GRACE: Duration = Duration::from_secs(5);
 
+/// `child.wait()` with a watchdog: on Unix we poll so a child whose process group has vanished
+/// without ever being reaped can't wedge the supervisor thread forever.
+fn wait_for_exit(child: &mut Child) -> std::io::Result<ExitStatus> {
     #[cfg(unix)]
     {
-        // Gracefully stop the whole process tree.
-        signal_process_group(pid, libc::SIGTERM);
+        let pgid = child.id();
+        let mut vanished_since: Option<Instant> = None;
+        loop {
+            if let Some(status) = child.try_wait()? {
+                return Ok(status);
+            }
 
-        // If it doesn't die quickly, force kill.
-        let manager = manager.clone();
-        thread::spawn(move || {
-            thread::sleep(Duration::from_secs(3));
-            let pid = {
-                let map = manager.processes.lock().ok();
-                map.and_then(|map| map.get(&key).map(|p| p.pid)).unwrap_or(pid)
-            };
-            if process_group_exists(pid) {
-                signal_process_group(pid, libc::SIGKILL);
+            if process_group_exists(pgid) {
+                vanished_since = None;
+            } else {
+                let since = *vanished_since.get_or_insert_with(Instant::now);
+                if since.elapsed() > WAIT_WATCHDOG_GRACE {
+                    return Err(std::io::Error::other(
+                        "process group exited but wait() never returned",
+                    ));
+                }
             }
-        });
+
+            thread::sleep(Duration::from_millis(100));
+        }
     }
 
-    Ok(())
+    #[cfg(not(unix))]
+    {
+        child.wait()
+    }
 }
 
-#[tauri::command(rename_all = "camelCase")]
-fn write_to_process(
-    state: State<ProcessManager>,
-    project_path: String,
-    process_name: String,
-    input: String,
-) -> Result<(), String> {
-    let key = process_key(&project_path, &process_name);
-    let stdin = {
-        let map = state
-            .processes
-            .lock()
-            .map_err(|_| "Process map poisoned".to_string())?;
-        let Some(entry) = map.get(&key) else {
-            return Err("Process not running".to_string());
-        };
-        entry.stdin.clone()
-    };
-
-    let mut handle = stdin
-        .lock()
-        .map_err(|_| "Process stdin poisoned".to_string())?;
-    if let Some(stdin) = handle.as_mut() {
-        stdin
-            .write_all(input.as_bytes())
-            .map_err(|err| format!("Failed to write to stdin: {}", err))?;
-        stdin
-            .flush()
-            .map_err(|err| format!("Failed to flush stdin: {}", err))?;
-        Ok(())
+/// `$SHELL` if set, then the `default_shell` setting, then zsh (or sh where zsh is missing).
+fn login_shell(settings: &AppSettings) -> String {
+    if let Some(shell) = env::var("SHELL").ok().filter(|shell| !shell.is_empty()) {
+        return shell;
+    }
+    if let Some(shell) = &settings.default_shell {
+        return shell.clone();
+    }
+    if Path::new("/bin/zsh").exists() {
+        "/bin/zsh".to_string()
     } else {
-        Err("Process stdin not available".to_string())
+        "/bin/sh".to_string()
     }
 }
 
-#[tauri::command(rename_all = "camelCase")]
-fn run_action(project_path: String, command: String) -> Result<(), String> {
-    if command.trim().is_empty() {
-        return Err("Missing command".to_string());
+fn validate_shell(shell: &str) -> Result<(), String> {
+    let metadata =
+        fs::metadata(shell).map_err(|err| format!("Shell {} is unavailable: {}", shell, err))?;
+    if !metadata.is_file() {
+        return Err(format!("Shell {} is not a file", shell));
     }
 
-    let mut cmd = Command::new("/bin/zsh");
-    cmd.arg("-lc")
-        .arg(&command)
-        .current_dir(&project_path)
-        .stdin(Stdio::null())
-        .stdout(Stdio::null())
-        .stderr(Stdio::null());
-
     #[cfg(unix)]
     {
-        use std::os::unix::process::CommandExt;
-        unsafe {
-            cmd.pre_exec(|| {
-                // Detach from the app process so actions can open external apps freely.
-                libc::setsid();
-                Ok(())
-            });
+        use std::os::unix::fs::PermissionsExt;
+        if metadata.permissions().mode() & 0o111 == 0 {
+            return Err(format!("Shell {} is not executable", shell));
         }
     }
 
-    let mut child = cmd
-        .spawn()
-        .map_err(|err| format!("Failed to run action: {}", err))?;
+    Ok(())
+}
 
-    // Reap in background to avoid zombies, but don't track as a managed process.
-    thread::spawn(move || {
-        let _ = child.wait();
-    });
-
-    Ok(())
+fn config_path_candidates(project_path: &Path) -> Vec<PathBuf> {
+    vec![
+        project_path.join("myterm.yml"),
+        project_path.join("myterm.yaml"),
+    ]
 }
 
-#[tauri::command(rename_all = "camelCase")]
-fn check_for_update(app: AppHandle) -> Result<UpdateInfo, String> {
-    let current_version = app.package_info().version.to_string();
+/// The process's config, or `None` when the project has no config file or the file doesn't
+/// list the process. A file that fails to load is an error rather than `None`, so a typo can't
+/// silently run the process without its `env`, `cwd` or `container`.
+fn configured_process(
+    project_path: &Path,
+    process_name: &str,
+) -> Result<Option<ProcessConfig>, String> {
+    if find_existing_config_path(project_path).is_none() {
+        return Ok(None);
+    }
+    Ok(read_project_config(project_path)?
+        .processes
+        .into_iter()
+        .find(|process| process.name == process_name))
+}
 
-    let output = Command::new("curl")
-        .args(["-sL", "-H", "Accept: application/vnd.github+json",
-               "https://api.github.com/repos/porterabbott/myterm/releases/latest"])
-        .output()
-        .map_err(|err| format!("Failed to fetch updates: {}", err))?;
+/// Looks up one process in the project's config. Any rules that derive the effective config
+/// from the file belong here so every caller sees the same result.
+fn resolve_process_config(
+    project_path: &Path,
+    process_name: &str,
+) -> Result<ProcessConfig, String> {
+    read_project_config(project_path)?
+        .processes
+        .into_iter()
+        .find(|process| process.name == process_name)
+        .ok_or_else(|| format!("Process {} not found in config", process_name))
+}
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("gh api failed: {}", stderr.trim()));
+/// Builds the `PATH` for a process with `path_prepend` entries (resolved against `cwd`) in front
+/// of `base` (the inherited value when `None`). Returns `None` when there is nothing to prepend.
+fn prepended_search_path(
+    cwd: &Path,
+    path_prepend: &[String],
+    base: Option<&str>,
+) -> Result<Option<String>, String> {
+    if path_prepend.is_empty() {
+        return Ok(None);
     }
 
-    let release: GithubRelease =
-        serde_json::from_slice(&output.stdout).map_err(|err| err.to_string())?;
-    let latest_tag = release.tag_name.clone();
-    let latest_version = latest_tag.trim_start_matches('v');
-    let available = is_newer_version(latest_version, &current_version);
+    let mut paths: Vec<PathBuf> = path_prepend.iter().map(|entry| cwd.join(entry)).collect();
+    if let Some(current) = base.map(Into::into).or_else(|| env::var_os("PATH")) {
+        paths.extend(env::split_paths(&current));
+    }
+    env::join_paths(paths)
+        .map(|joined| Some(joined.to_string_lossy().to_string()))
+        .map_err(|err| format!("Invalid path_prepend entry: {}", err))
+}
 
-    let download_url = if available {
-        release
-            .assets
+/// Parses `KEY=value` lines, skipping blanks and `#` comments. An `export ` prefix and matching
+/// surrounding quotes are stripped.
+fn parse_env_file(contents: &str) -> Result<Vec<(String, String)>, String> {
+    let mut vars = Vec::new();
+    for (index, line) in normalize_config_text(contents).lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!("line {}: expected KEY=value", index + 1));
+        };
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(format!("line {}: missing variable name", index + 1));
+        }
+        let value = value.trim();
+        let unquoted = ['"', '\'']
             .iter()
-            .find(|asset| asset.name == "MyTerm.zip")
-            .map(|asset| asset.browser_download_url.clone())
-            .ok_or_else(|| "Update available, but MyTerm.zip asset not found".to_string())?
-    } else {
-        String::new()
+            .find_map(|quote| value.strip_prefix(*quote)?.strip_suffix(*quote))
+            .unwrap_or(value);
+        vars.push((key.to_string(), unquoted.to_string()));
+    }
+    Ok(vars)
+}
+
+/// Expands `$VAR`, `${VAR}` and `${VAR:-default}` in a shell command using `lookup`, the way sh
+/// would but before the shell runs. Values are quoted so they stay one word and aren't run as
+/// shell code. Nothing inside single quotes or after a backslash is touched, and names `lookup`
+/// can't resolve are left for the shell (e.g. ones only the login shell's rc exports). A default
+/// applies when the variable is unset or empty. `$$` is an escape for a single `$`.
+fn expand_env_vars(command: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let is_name = |name: &str| {
+        name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
     };
+    let mut expanded = String::with_capacity(command.len());
+    let mut in_double_quotes = false;
+    let mut rest = command;
+    while let Some(special) = rest.find(['$', '\'', '"', '\\']) {
+        expanded.push_str(&rest[..special]);
+        let after = &rest[special + 1..];
+        match rest[special..].chars().next() {
+            Some('\\') => {
+                // Keep the escaped character as is, whatever it is.
+                let escaped = after.chars().next().map_or(0, char::len_utf8);
+                expanded.push('\\');
+                expanded.push_str(&after[..escaped]);
+                rest = &after[escaped..];
+                continue;
+            }
+            Some('\'') if !in_double_quotes => {
+                let end = after.find('\'').map_or(after.len(), |end| end + 1);
+                expanded.push('\'');
+                expanded.push_str(&after[..end]);
+                rest = &after[end..];
+                continue;
+            }
+            Some(quote @ ('\'' | '"')) => {
+                in_double_quotes ^= quote == '"';
+                expanded.push(quote);
+                rest = after;
+                continue;
+            }
+            _ => {}
+        }
+        if let Some(tail) = after.strip_prefix('$') {
+            expanded.push('$');
+            rest = tail;
+            continue;
+        }
+        let quote = |value: &str| {
+            if in_double_quotes {
+                value
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"")
+                    .replace('$', "\\$")
+                    .replace('`', "\\`")
+            } else {
+                shell_quote(value)
+            }
+        };
+        let braced = after
+            .strip_prefix('{')
+            .and_then(|braced| braced.split_once('}'));
+        if let Some((inner, tail)) = braced {
+            let (name, default) = match inner.split_once(":-") {
+                Some((name, default)) => (name, Some(default)),
+                None => (inner, None),
+            };
+            if is_name(name) {
+                let value = lookup(name).filter(|value| default.is_none() || !value.is_empty());
+                match value.as_deref().or(default) {
+                    Some(value) => expanded.push_str(&quote(value)),
+                    None => expanded.push_str(&rest[special..rest.len() - tail.len()]),
+                }
+                rest = tail;
+                continue;
+            }
+        }
+        let end = after
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .unwrap_or(after.len());
+        match lookup(&after[..end]).filter(|_| is_name(&after[..end])) {
+            Some(value) => expanded.push_str(&quote(&value)),
+            None => expanded.push_str(&rest[special..special + 1 + end]),
+        }
+        rest = &after[end..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
 
-    Ok(UpdateInfo {
-        available,
-        version: latest_tag,
-        download_url,
-    })
+fn read_env_file(path: &Path) -> Result<Vec<(String, String)>, String> {
+    let contents = fs::read_to_string(path).map_err(|err| {
+        if err.kind() == std::io::ErrorKind::NotFound {
+            format!("env_from_file not found: {}", path.display())
+        } else {
+            format!("{} ({})", err, path.display())
+        }
+    })?;
+    parse_env_file(&contents).map_err(|err| format!("{} ({})", err, path.display()))
 }
 
-#[tauri::command(rename_all = "camelCase")]
-fn install_update(download_url: String) -> Result<(), String> {
-    if download_url.trim().is_empty() {
-        return Err("Missing download URL".to_string());
+fn find_existing_config_path(project_path: &Path) -> Option<PathBuf> {
+    config_path_candidates(project_path)
+        .into_iter()
+        .find(|candidate| candidate.exists())
+}
+
+/// Strips a UTF-8 BOM and converts CRLF line endings so Windows-edited configs parse cleanly.
+fn normalize_config_text(contents: &str) -> String {
+    contents
+        .strip_prefix('\u{feff}')
+        .unwrap_or(contents)
+        .replace("\r\n", "\n")
+}
+
+fn read_project_config(project_path: &Path) -> Result<ProjectConfig, String> {
+    for candidate in config_path_candidates(project_path) {
+        match std::fs::read_to_string(&candidate) {
+            Ok(contents) => {
+                let normalized = normalize_config_text(&contents);
+                check_config_version(&normalized)
+                    .map_err(|err| format!("{} ({})", err, candidate.display()))?;
+                let mut config: ProjectConfig = serde_yaml::from_str(&normalized)
+                    .map_err(|err| format!("{} ({})", err, candidate.display()))?;
+                validate_config(&config)
+                    .map_err(|err| format!("{} ({})", err, candidate.display()))?;
+                config.apply_project_settings(project_path);
+                return Ok(config);
+            }
+            Err(err) => {
+                if err.kind() == std::io::ErrorKind::NotFound {
+                    continue;
+                }
+                return Err(format!("{} ({})", err, candidate.display()));
+            }
+        }
     }
 
-    let app_bundle = find_app_bundle_path()?;
-    let _app_parent = app_bundle
-        .parent()
-        .ok_or_else(|| "Could not determine app bundle parent".to_string())?;
+    Err("Missing myterm.yml".to_string())
+}
 
-    let temp_dir = create_temp_dir()?;
-    let zip_path = temp_dir.join("MyTerm.zip");
-    let extract_dir = temp_dir.join("extract");
-    fs::create_dir_all(&extract_dir).map_err(|err| err.to_string())?;
+/// Checks that go beyond what deserializing can express.
+fn validate_config(config: &ProjectConfig) -> Result<(), String> {
+    for process in &config.processes {
+        validate_command(&process.name, &process.command)?;
+        validate_stop_sequence(process)?;
+    }
+    Ok(())
+}
 
-    // Use gh CLI to download the asset (handles auth for private repos)
-    let dl_status = Command::new("curl")
-        .args(["-sL", "-o"])
-        .arg(&zip_path)
-        .arg(&download_url)
-        .status()
-        .map_err(|err| format!("Failed to run gh CLI: {}", err))?;
+/// Every signal must be known, and the last one must be `KILL` so a stop always ends.
+fn validate_stop_sequence(process: &ProcessConfig) -> Result<(), String> {
+    let Some(last) = process.stop_sequence.last() else {
+        return Ok(());
+    };
+    #[cfg(unix)]
+    for step in &process.stop_sequence {
+        parse_signal(&step.signal)
+            .map_err(|err| format!("Process {} stop_sequence: {}", process.name, err))?;
+    }
+    let last = last.signal.trim().to_ascii_uppercase();
+    if last.strip_prefix("SIG").unwrap_or(&last) != "KILL" {
+        return Err(format!(
+            "Process {} stop_sequence must end with KILL",
+            process.name
+        ));
+    }
+    Ok(())
+}
 
-    if !dl_status.success() {
-        return Err("gh release download failed".to_string());
+/// An empty command would exit immediately and, with autorestart, spin forever.
+fn validate_command(process_name: &str, command: &str) -> Result<(), String> {
+    if command.trim().is_empty() {
+        return Err(format!("Process {} has no command", process_name));
     }
+    Ok(())
+}
 
-    let unzip_status = Command::new("unzip")
-        .arg("-q")
-        .arg(&zip_path)
-        .arg("-d")
-        .arg(&extract_dir)
-        .status()
-        .map_err(|err| err.to_string())?;
+#[cfg(unix)]
+fn signal_process_group(pgid: u32, signal: i32) {
+    if pgid == 0 {
+        return;
+    }
 
-    if !unzip_status.success() {
-        return Err("Failed to unzip update".to_string());
+    unsafe {
+        // Send to the entire process group to avoid orphaned children.
+        // Equivalent to: kill(-pgid, signal)
+        let _ = libc::kill(-(pgid as i32), signal);
     }
+}
 
-    let extracted_app = find_app_in_dir(&extract_dir)
-        .ok_or_else(|| "Could not locate extracted .app bundle".to_string())?;
+/// A Job Object holding one run's process tree, so the whole tree can be ended at once like a
+/// Unix process group. It's kill-on-close: dropping the last handle (including when myterm
+/// itself dies) ends whatever is still in it.
+#[cfg(windows)]
+struct JobObject(windows_sys::Win32::Foundation::HANDLE);
+
+// The handle is only passed to Win32 calls, which may be made from any thread.
+#[cfg(windows)]
+unsafe impl Send for JobObject {}
+#[cfg(windows)]
+unsafe impl Sync for JobObject {}
+
+#[cfg(windows)]
+impl JobObject {
+    fn assign(child: &Child) -> std::io::Result<Self> {
+        use std::os::windows::io::AsRawHandle;
+        use windows_sys::Win32::System::JobObjects::{
+            AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+            SetInformationJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+            JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+        };
 
-    let _ = Command::new("xattr")
-        .arg("-cr")
-        .arg(&extracted_app)
-        .status();
+        unsafe {
+            let handle = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+            if handle.is_null() {
+                return Err(std::io::Error::last_os_error());
+            }
+            let job = JobObject(handle);
+
+            let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+            info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+            let set = SetInformationJobObject(
+                job.0,
+                JobObjectExtendedLimitInformation,
+                &info as *const _ as *const std::ffi::c_void,
+                std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+            );
+            if set == 0 || AssignProcessToJobObject(job.0, child.as_raw_handle()) == 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(job)
+        }
+    }
 
-    // Move old bundle aside (keeps running binary intact), copy new one in, then clean up
-    let backup_bundle = app_bundle.with_extension("app.old");
-    let _ = Command::new("rm").args(["-rf"]).arg(&backup_bundle).status();
+    fn terminate(&self) {
+        unsafe {
+            let _ = windows_sys::Win32::System::JobObjects::TerminateJobObject(self.0, 1);
+        }
+    }
+}
 
-    let mv_status = Command::new("mv")
-        .arg(&app_bundle)
-        .arg(&backup_bundle)
-        .status()
-        .map_err(|err| err.to_string())?;
+#[cfg(windows)]
+impl Drop for JobObject {
+    fn drop(&mut self) {
+        unsafe {
+            windows_sys::Win32::Foundation::CloseHandle(self.0);
+        }
+    }
+}
 
-    if !mv_status.success() {
-        return Err("Failed to move old app bundle".to_string());
+/// Accepts `HUP`, `SIGHUP` or `hup` style names for the signals a reload or a `stop_sequence`
+/// might use.
+#[cfg(unix)]
+fn parse_signal(name: &str) -> Result<i32, String> {
+    let upper = name.trim().to_ascii_uppercase();
+    match upper.strip_prefix("SIG").unwrap_or(&upper) {
+        "HUP" => Ok(libc::SIGHUP),
+        "INT" => Ok(libc::SIGINT),
+        "QUIT" => Ok(libc::SIGQUIT),
+        "TERM" => Ok(libc::SIGTERM),
+        "KILL" => Ok(libc::SIGKILL),
+        "USR1" => Ok(libc::SIGUSR1),
+        "USR2" => Ok(libc::SIGUSR2),
+        "WINCH" => Ok(libc::SIGWINCH),
+        _ => Err(format!("Unsupported signal: {}", name)),
     }
+}
 
-    let copy_status = Command::new("cp")
-        .args(["-R"])
-        .arg(&extracted_app)
-        .arg(&app_bundle)
-        .status()
-        .map_err(|err| err.to_string())?;
+#[cfg(unix)]
+fn process_group_exists(pgid: u32) -> bool {
+    if pgid == 0 {
+        return false;
+    }
 
-    if !copy_status.success() {
-        return Err("Failed to copy new app bundle".to_string());
+    unsafe {
+        let result = libc::kill(-(pgid as i32), 0);
+        if result == 0 {
+            return true;
+        }
+
+        // ESRCH => no such process / group
+        std::io::Error::last_os_error().raw_os_error() != Some(libc::ESRCH)
     }
+}
 
-    let _ = Command::new("xattr")
-        .arg("-cr")
-        .arg(&app_bundle)
-        .status();
+#[cfg(unix)]
+fn process_exists(pid: u32) -> bool {
+    if pid == 0 {
+        return false;
+    }
 
-    Ok(())
+    let result = unsafe { libc::kill(pid as i32, 0) };
+    result == 0 || std::io::Error::last_os_error().raw_os_error() != Some(libc::ESRCH)
 }
 
-fn spawn_restart_helper(app_bundle: &Path, backup_bundle: &Path) -> Result<(), String> {
-    let temp_dir = create_temp_dir()?;
-    let script_path = temp_dir.join("restart.sh");
-    let script = r#"#!/bin/sh
-TARGET_PID="$MYTERM_PID"
-APP_BUNDLE="$MYTERM_APP"
-BACKUP_BUNDLE="$MYTERM_BACKUP"
+/// A process we're not allowed to query (e.g. another user's) counts as running.
+#[cfg(windows)]
+fn process_exists(pid: u32) -> bool {
+    use windows_sys::Win32::Foundation::{
+        CloseHandle, GetLastError, ERROR_ACCESS_DENIED, STILL_ACTIVE,
+    };
+    use windows_sys::Win32::System::Threading::{
+        GetExitCodeProcess, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
 
-i=0
-while [ $i -lt 15 ]; do
-  if ! kill -0 "$TARGET_PID" 2>/dev/null; then
-    break
-  fi
-  sleep 0.1
-  i=$((i+1))
-done
+    if pid == 0 {
+        return false;
+    }
 
-sleep 0.5
-/usr/bin/open -n "$APP_BUNDLE" >/dev/null 2>&1
-sleep 1
-/bin/rm -rf "$BACKUP_BUNDLE" >/dev/null 2>&1
-"#;
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return GetLastError() == ERROR_ACCESS_DENIED;
+        }
+        // An exited process stays openable while anyone holds a handle, so check it's still
+        // running rather than just present.
+        let mut exit_code = 0u32;
+        let queried = GetExitCodeProcess(handle, &mut exit_code);
+        CloseHandle(handle);
+        queried != 0 && exit_code == STILL_ACTIVE as u32
+    }
+}
 
-    fs::write(&script_path, script).map_err(|err| err.to_string())?;
+fn project_lock_path(project_path: &Path) -> PathBuf {
+    project_path.join(".myterm").join("lock")
+}
 
-    let mut cmd = Command::new("/bin/sh");
-    cmd.arg(&script_path)
-        .env("MYTERM_PID", format!("{}", std::process::id()))
-        .env("MYTERM_APP", app_bundle)
-        .env("MYTERM_BACKUP", backup_bundle)
-        .stdin(Stdio::null())
-        .stdout(Stdio::null())
-        .stderr(Stdio::null());
+/// Claims `.myterm/lock` for this instance so two myterm instances don't both manage the same
+/// project. A lock left behind by a dead process is taken over.
+fn acquire_project_lock(manager: &ProcessManager, project_path: &Path) -> Result<(), String> {
+    let mut locked = manager
+        .locked_projects
+        .lock()
+        .map_err(|_| "Project locks poisoned".to_string())?;
+    if locked.contains(project_path) {
+        return Ok(());
+    }
 
-    #[cfg(unix)]
-    {
-        use std::os::unix::process::CommandExt;
-        unsafe {
-            cmd.pre_exec(|| {
-                libc::setsid();
-                Ok(())
-            });
+    let lock_path = project_lock_path(project_path);
+    let owner = fs::read_to_string(&lock_path)
+        .ok()
+        .and_then(|contents| contents.trim().parse::<u32>().ok());
+    if let Some(owner) = owner {
+        if owner != std::process::id() && process_exists(owner) {
+            return Err(format!(
+                "Project managed by another myterm instance (pid {})",
+                owner
+            ));
         }
     }
 
-    cmd.spawn().map_err(|err| err.to_string())?;
+    if let Some(parent) = lock_path.parent() {
+        fs::create_dir_all(parent).map_err(|err| format!("{} ({})", err, parent.display()))?;
+    }
+    fs::write(&lock_path, std::process::id().to_string())
+        .map_err(|err| format!("{} ({})", err, lock_path.display()))?;
+    locked.insert(project_path.to_path_buf());
     Ok(())
 }
 
-#[tauri::command(rename_all = "camelCase")]
-fn restart_app(app: AppHandle, state: State<RestartState>) -> Result<(), String> {
-    let running_bundle = find_app_bundle_path()?;
-    let app_bundle = resolve_primary_app_bundle_path(&running_bundle);
-    let backup_bundle = app_bundle.with_extension("app.old");
+fn release_project_locks(manager: &ProcessManager) {
+    let Ok(mut locked) = manager.locked_projects.lock() else {
+        return;
+    };
+    let own_pid = std::process::id().to_string();
+    for project_path in locked.drain() {
+        let lock_path = project_lock_path(&project_path);
+        let ours = fs::read_to_string(&lock_path)
+            .map(|contents| contents.trim() == own_pid)
+            .unwrap_or(false);
+        if ours {
+            let _ = fs::remove_file(&lock_path);
+        }
+    }
+}
 
-    if !app_bundle.exists() {
-        return Err(format!(
-            "Updated app bundle not found at {}",
-            app_bundle.display()
-        ));
+/// Stops every managed process and releases our project locks. Returns the processes signalled.
+/// Containers are asked to stop within `grace` in the background; join the returned handles to
+/// wait for that.
+fn stop_all_processes(
+    manager: &ProcessManager,
+    grace: Duration,
+) -> (Vec<ProcessSnapshot>, Vec<thread::JoinHandle<()>>) {
+    let mut signalled = Vec::new();
+    let mut container_stops = Vec::new();
+
+    if let Ok(map) = manager.processes.lock() {
+        for entry in map.values() {
+            entry.stop_flag.store(true, Ordering::SeqCst);
+            if let Some(container) = entry.container.clone() {
+                container_stops.push(thread::spawn(move || container.stop(grace)));
+            }
+            #[cfg(windows)]
+            if let Some(job) = &entry.job {
+                job.terminate();
+            }
+            if entry.pid > 0 {
+                signalled.push(ProcessSnapshot {
+                    project_path: entry.project_path.clone(),
+                    process_name: entry.process_name.get(),
+                    pid: entry.pid,
+                    status: entry.status.clone(),
+                    autorestart: entry.autorestart.load(Ordering::SeqCst),
+                });
+            }
+        }
     }
 
-    // Stop all managed processes before exiting (so we don't orphan dev servers)
-    let manager = app.state::<ProcessManager>();
-    let pgids = stop_all_processes(manager.inner());
     #[cfg(unix)]
     {
-        wait_then_force_kill(pgids, Duration::from_millis(500), Duration::from_millis(500));
+        for process in &signalled {
+            signal_process_group(process.pid, libc::SIGTERM);
+        }
     }
 
-    spawn_restart_helper(&app_bundle, &backup_bundle)?;
-    state.mark_update_restart();
-    // Hard exit — bypass Tauri window cleanup to avoid hangs
-    // The setsid helper script survives this and relaunches the app
-    std::process::exit(0);
+    release_project_locks(manager);
+    (signalled, container_stops)
 }
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
-    tauri::Builder::default()
-        .plugin(tauri_plugin_opener::init())
-        .plugin(tauri_plugin_dialog::init())
-        .manage(ProcessManager::default())
+/// Stops everything before exit: SIGTERM, then SIGKILL for groups still alive after `grace`.
+/// Groups that survive even that (e.g. stuck in uninterruptible sleep) are reported to `sink`
+/// rather than left behind silently.
+fn shutdown_all_processes(manager: &ProcessManager, sink: &EventSink, grace: Duration) {
+    let (signalled, container_stops) = stop_all_processes(manager, grace);
+    #[cfg(unix)]
+    {
+        let pgids = signalled.iter().map(|process| process.pid).collect();
+        let survivors = wait_then_force_kill(pgids, grace, grace, SHUTDOWN_POLL_INTERVAL);
+        if !survivors.is_empty() {
+            let stuck = signalled
+                .into_iter()
+                .filter(|process| survivors.contains(&process.pid))
+                .collect();
+            sink.shutdown_stuck(stuck);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = (signalled, sink);
+    // `docker stop` outlives the CLI we signalled; don't exit before the containers are gone.
+    for stop in container_stops {
+        let _ = stop.join();
+    }
+}
+
+/// Default time between liveness checks while waiting for process groups to exit.
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Time `stop_process` gives a process after SIGTERM before escalating to SIGKILL, unless
+/// `stop_timeout_secs` is set.
+const STOP_GRACE_PERIOD: Duration = Duration::from_secs(3);
+
+/// Polls every `poll` until all groups have exited or `timeout` elapses. Returns true if they
+/// all exited.
+#[cfg(unix)]
+fn wait_for_groups_exit(pgids: &[u32], timeout: Duration, poll: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if pgids.iter().all(|pgid| !process_group_exists(*pgid)) {
+            return true;
+        }
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return false;
+        }
+        thread::sleep(poll.min(remaining));
+    }
+}
+
+/// Returns the groups still alive after the hard-kill window.
+#[cfg(unix)]
+fn wait_then_force_kill(
+    pgids: Vec<u32>,
+    wait_for: Duration,
+    hard_kill_after: Duration,
+    poll: Duration,
+) -> Vec<u32> {
+    // Give processes a moment to exit cleanly.
+    if wait_for_groups_exit(&pgids, wait_for, poll) {
+        return Vec::new();
+    }
+
+    // Still alive? Force kill.
+    for pgid in &pgids {
+        if process_group_exists(*pgid) {
+            signal_process_group(*pgid, libc::SIGKILL);
+        }
+    }
+
+    // Optionally wait a tiny bit more, but don't block too long on shutdown.
+    wait_for_groups_exit(&pgids, hard_kill_after, poll);
+    pgids
+        .into_iter()
+        .filter(|pgid| process_group_exists(*pgid))
+        .collect()
+}
+
+fn detect_project_name(project_path: &Path) -> String {
+    project_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("project")
+        .to_string()
+}
+
+/// Most processes offered for a workspace; big monorepos would otherwise flood the config.
+const MAX_WORKSPACE_PROCESSES: usize = 8;
+
+/// Workspace globs from `package.json` (`workspaces` as an array or `{ packages: [...] }`),
+/// falling back to `pnpm-workspace.yaml`.
+fn workspace_patterns(project_path: &Path, package_json: &serde_json::Value) -> Vec<String> {
+    let workspaces = package_json.get("workspaces");
+    let listed = workspaces
+        .and_then(|w| w.as_array().or_else(|| w.get("packages")?.as_array()))
+        .map(|patterns| {
+            patterns
+                .iter()
+                .filter_map(|pattern| pattern.as_str().map(str::to_string))
+                .collect()
+        });
+    listed
+        .or_else(|| {
+            let contents = fs::read_to_string(project_path.join("pnpm-workspace.yaml")).ok()?;
+            let yaml: serde_yaml::Value = serde_yaml::from_str(&contents).ok()?;
+            let packages = yaml.get("packages")?.as_sequence()?;
+            Some(
+                packages
+                    .iter()
+                    .filter_map(|pattern| pattern.as_str().map(str::to_string))
+                    .collect(),
+            )
+        })
+        .unwrap_or_default()
+}
+
+/// Expands `dir/*` (and `dir/**`, one level deep) or a literal path. Negated and other glob
+/// patterns are skipped.
+fn expand_workspace_pattern(project_path: &Path, pattern: &str) -> Vec<PathBuf> {
+    let pattern = pattern.trim_start_matches("./");
+    if pattern.starts_with('!') {
+        return Vec::new();
+    }
+    let Some(parent) = pattern
+        .strip_suffix("/**")
+        .or_else(|| pattern.strip_suffix("/*"))
+    else {
+        if pattern.contains('*') {
+            return Vec::new();
+        }
+        return vec![project_path.join(pattern)];
+    };
+    if parent.contains('*') {
+        return Vec::new();
+    }
+    let Ok(entries) = fs::read_dir(project_path.join(parent)) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// One process per workspace package with a `dev` (or else `start`) script, run from the root
+/// through the package manager's workspace flag.
+fn guess_workspace_processes(
+    project_path: &Path,
+    package_json: &serde_json::Value,
+    pm: &str,
+) -> Vec<ProcessConfig> {
+    let mut processes = Vec::new();
+    for pattern in workspace_patterns(project_path, package_json) {
+        for dir in expand_workspace_pattern(project_path, &pattern) {
+            if processes.len() == MAX_WORKSPACE_PROCESSES {
+                return processes;
+            }
+            let Ok(contents) = fs::read_to_string(dir.join("package.json")) else {
+                continue;
+            };
+            let Ok(json) = serde_json::from_str::<serde_json::Value>(&contents) else {
+                continue;
+            };
+            let scripts = json.get("scripts");
+            let Some(script) = ["dev", "start"].into_iter().find(|script| {
+                scripts
+                    .and_then(|s| s.get(script))
+                    .is_some_and(|v| v.is_string())
+            }) else {
+                continue;
+            };
+            let dir_name = dir
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let package = json
+                .get("name")
+                .and_then(|name| name.as_str())
+                .map(str::to_string)
+                .unwrap_or(dir_name);
+            if processes.iter().any(|p: &ProcessConfig| p.name == package) {
+                continue;
+            }
+            let command = match pm {
+                "yarn" => format!("yarn workspace {} {}", package, script),
+                "pnpm" => format!("pnpm --filter {} {}", package, script),
+                "bun" => format!("bun run --filter {} {}", package, script),
+                _ => format!("npm run {} -w {}", script, package),
+            };
+            processes.push(ProcessConfig {
+                name: package,
+                command,
+                autorestart: true,
+                ..Default::default()
+            });
+        }
+    }
+    processes
+}
+
+fn guess_processes(project_path: &Path) -> Vec<ProcessConfig> {
+    let procfile_path = project_path.join("Procfile");
+    if let Ok(contents) = std::fs::read_to_string(&procfile_path) {
+        let mut processes = Vec::new();
+        for line in contents.lines() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let Some((name, cmd)) = trimmed.split_once(':') else {
+                continue;
+            };
+            let name = name.trim();
+            let cmd = cmd.trim();
+            if name.is_empty() || cmd.is_empty() {
+                continue;
+            }
+            processes.push(ProcessConfig {
+                name: name.to_string(),
+                command: cmd.to_string(),
+                autorestart: true,
+                ..Default::default()
+            });
+        }
+        if !processes.is_empty() {
+            return processes;
+        }
+    }
+
+    let package_json_path = project_path.join("package.json");
+    if let Ok(contents) = std::fs::read_to_string(&package_json_path) {
+        if let Ok(json) = serde_json::from_str::<serde_json::Value>(&contents) {
+            let scripts = json.get("scripts");
+            let has_dev = scripts
+                .and_then(|s| s.get("dev"))
+                .and_then(|v| v.as_str())
+                .is_some();
+            let has_start = scripts
+                .and_then(|s| s.get("start"))
+                .and_then(|v| v.as_str())
+                .is_some();
+
+            let pm = if project_path.join("pnpm-lock.yaml").exists() {
+                "pnpm"
+            } else if project_path.join("yarn.lock").exists() {
+                "yarn"
+            } else if project_path.join("bun.lockb").exists() {
+                "bun"
+            } else {
+                "npm"
+            };
+
+            if has_dev || has_start {
+                let script = if has_dev { "dev" } else { "start" };
+
+                let cmd = match (pm, script) {
+                    ("yarn", "dev") => "yarn dev".to_string(),
+                    ("yarn", "start") => "yarn start".to_string(),
+                    ("pnpm", "dev") => "pnpm dev".to_string(),
+                    ("pnpm", "start") => "pnpm start".to_string(),
+                    ("bun", "dev") => "bun run dev".to_string(),
+                    ("bun", "start") => "bun run start".to_string(),
+                    (_, "dev") => "npm run dev".to_string(),
+                    (_, "start") => "npm start".to_string(),
+                    _ => "npm run dev".to_string(),
+                };
+
+                return vec![ProcessConfig {
+                    name: script.to_string(),
+                    command: cmd,
+                    autorestart: true,
+                    ..Default::default()
+                }];
+            }
+
+            let workspace_processes = guess_workspace_processes(project_path, &json, pm);
+            if !workspace_processes.is_empty() {
+                return workspace_processes;
+            }
+        }
+    }
+
+    let script_processes = guess_script_processes(project_path);
+    if !script_processes.is_empty() {
+        return script_processes;
+    }
+
+    vec![ProcessConfig {
+        name: "dev".to_string(),
+        command: "echo 'Edit myterm.yml to add processes' && sleep 2".to_string(),
+        ..Default::default()
+    }]
+}
+
+/// Most processes offered from `scripts/` and `bin/`.
+const MAX_SCRIPT_PROCESSES: usize = 8;
+
+/// Extensions of files in `scripts/` or `bin/` that aren't meant to be run.
+const NON_SCRIPT_EXTENSIONS: [&str; 7] = ["md", "txt", "json", "yml", "yaml", "toml", "lock"];
+
+/// One one-shot process per executable in `scripts/`, then `bin/`, named after the file and
+/// run by its relative path.
+fn guess_script_processes(project_path: &Path) -> Vec<ProcessConfig> {
+    let mut processes = Vec::new();
+    for dir in ["scripts", "bin"] {
+        let Ok(entries) = fs::read_dir(project_path.join(dir)) else {
+            continue;
+        };
+        let mut scripts: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| is_runnable_script(path))
+            .collect();
+        scripts.sort();
+        for script in scripts {
+            if processes.len() == MAX_SCRIPT_PROCESSES {
+                return processes;
+            }
+            let (Some(file_name), Some(stem)) = (script.file_name(), script.file_stem()) else {
+                continue;
+            };
+            let name = stem.to_string_lossy().to_string();
+            if processes.iter().any(|p: &ProcessConfig| p.name == name) {
+                continue;
+            }
+            processes.push(ProcessConfig {
+                name,
+                command: format!("./{}/{}", dir, file_name.to_string_lossy()),
+                autorestart: false,
+                ..Default::default()
+            });
+        }
+    }
+    processes
+}
+
+fn is_runnable_script(path: &Path) -> bool {
+    let hidden = path
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+    let not_a_script = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| NON_SCRIPT_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()));
+    let Ok(metadata) = fs::metadata(path) else {
+        return false;
+    };
+    if hidden || not_a_script || !metadata.is_file() {
+        return false;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    true
+}
+
+fn parse_version(version: &str) -> Vec<u32> {
+    version
+        .trim()
+        .trim_start_matches('v')
+        .split('.')
+        .map(|part| part.parse::<u32>().unwrap_or(0))
+        .collect()
+}
+
+fn is_newer_version(latest: &str, current: &str) -> bool {
+    let latest_parts = parse_version(latest);
+    let current_parts = parse_version(current);
+    let max_len = latest_parts.len().max(current_parts.len());
+
+    for idx in 0..max_len {
+        let latest_value = *latest_parts.get(idx).unwrap_or(&0);
+        let current_value = *current_parts.get(idx).unwrap_or(&0);
+        if latest_value > current_value {
+            return true;
+        }
+        if latest_value < current_value {
+            return false;
+        }
+    }
+
+    false
+}
+
+fn is_backup_bundle(path: &Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()) == Some("old")
+        && path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.ends_with(".app.old"))
+            .unwrap_or(false)
+}
+
+/// Error returned when the binary isn't inside a `.app` (e.g. `cargo tauri dev`). It's a fixed
+/// string so the UI can recognise it and present self-update as unavailable, not as a failure.
+const NOT_IN_APP_BUNDLE: &str =
+    "Not running from an app bundle; self-update is unavailable in development builds";
+
+fn find_app_bundle_path() -> Result<PathBuf, String> {
+    let exe_path = env::current_exe().map_err(|err| err.to_string())?;
+    for ancestor in exe_path.ancestors() {
+        if ancestor.extension().and_then(|ext| ext.to_str()) == Some("app") {
+            return Ok(ancestor.to_path_buf());
+        }
+        if is_backup_bundle(ancestor) {
+            return Ok(ancestor.to_path_buf());
+        }
+    }
+
+    Err(NOT_IN_APP_BUNDLE.to_string())
+}
+
+/// Counterpart of `NOT_IN_APP_BUNDLE` on Linux, where only AppImages can update themselves.
+const NOT_IN_APPIMAGE: &str =
+    "Not running from an AppImage; self-update is unavailable for this install";
+
+/// The AppImage file we were started from. The AppImage runtime exports it as `APPIMAGE`.
+fn find_appimage_path() -> Result<PathBuf, String> {
+    env::var_os("APPIMAGE")
+        .map(PathBuf::from)
+        .filter(|path| path.is_file())
+        .ok_or_else(|| NOT_IN_APPIMAGE.to_string())
+}
+
+/// What an update replaces and a relaunch reopens: the AppImage on Linux, the `.app` bundle
+/// elsewhere.
+fn installed_app_path() -> Result<PathBuf, String> {
+    if cfg!(target_os = "linux") {
+        find_appimage_path()
+    } else {
+        find_app_bundle_path()
+    }
+}
+
+fn resolve_primary_app_bundle_path(running_bundle: &Path) -> PathBuf {
+    if is_backup_bundle(running_bundle) {
+        running_bundle.with_extension("app")
+    } else {
+        running_bundle.to_path_buf()
+    }
+}
+
+fn create_temp_dir(prefix: &str) -> Result<PathBuf, String> {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|err| err.to_string())?
+        .as_millis();
+    let dir = env::temp_dir().join(format!("{}-{}", prefix, stamp));
+    fs::create_dir_all(&dir).map_err(|err| err.to_string())?;
+    Ok(dir)
+}
+
+/// Downloads the update zip into `temp_dir` and extracts it there, returning the checked
+/// `.app` bundle. The caller owns `temp_dir` and removes it whatever the outcome.
+fn fetch_app_bundle(
+    app: &AppHandle,
+    download_url: &str,
+    expected_sha256: Option<&str>,
+    temp_dir: &Path,
+) -> Result<PathBuf, String> {
+    let zip_path = temp_dir.join("MyTerm.zip");
+    let extract_dir = temp_dir.join("extract");
+    fs::create_dir_all(&extract_dir).map_err(|err| err.to_string())?;
+
+    download_update(app, download_url, &zip_path)?;
+    if let Some(expected) = expected_sha256 {
+        verify_sha256(&zip_path, expected)?;
+    }
+    // A truncated download fails the integrity test before anything is extracted.
+    test_zip(&zip_path)?;
+
+    let unzip_status = Command::new("unzip")
+        .arg("-q")
+        .arg(&zip_path)
+        .arg("-d")
+        .arg(&extract_dir)
+        .status()
+        .map_err(|err| err.to_string())?;
+
+    if !unzip_status.success() {
+        return Err("Failed to unzip update".to_string());
+    }
+
+    let extracted_app = find_app_in_dir(&extract_dir)
+        .ok_or_else(|| "Could not locate extracted .app bundle".to_string())?;
+    validate_app_bundle(&extracted_app)?;
+    Ok(extracted_app)
+}
+
+fn test_zip(zip_path: &Path) -> Result<(), String> {
+    let status = Command::new("unzip")
+        .arg("-tq")
+        .arg(zip_path)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|err| err.to_string())?;
+    if !status.success() {
+        return Err("Downloaded update is corrupt or incomplete".to_string());
+    }
+    Ok(())
+}
+
+fn find_app_in_dir(root: &Path) -> Option<PathBuf> {
+    let entries = fs::read_dir(root).ok()?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if path.extension().and_then(|ext| ext.to_str()) == Some("app") {
+                return Some(path);
+            }
+            if let Some(found) = find_app_in_dir(&path) {
+                return Some(found);
+            }
+        }
+    }
+    None
+}
+
+/// Turns an arbitrary project/process name into something safe for a launchd label or a
+/// systemd unit file name.
+fn service_slug(name: &str) -> String {
+    let slug: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    slug.trim_matches('-').to_string()
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// The variables written into service files: the project `env` with the process's `env` on
+/// top. `env_from_file` is left out so secrets don't get copied around.
+fn service_env(process: &ProcessConfig) -> BTreeMap<String, String> {
+    let mut env = process.project_env.clone();
+    env.extend(process.env.clone());
+    env
+}
+
+fn launchd_plist(label: &str, shell: &str, project_path: &Path, process: &ProcessConfig) -> String {
+    let args = [shell, "-lc", process.command.as_str()]
+        .iter()
+        .map(|arg| format!("        <string>{}</string>\n", xml_escape(arg)))
+        .collect::<String>();
+    let env = service_env(process);
+    let env = if env.is_empty() {
+        String::new()
+    } else {
+        let entries = env
+            .iter()
+            .map(|(key, value)| {
+                format!(
+                    "        <key>{}</key>\n        <string>{}</string>\n",
+                    xml_escape(key),
+                    xml_escape(value)
+                )
+            })
+            .collect::<String>();
+        format!(
+            "    <key>EnvironmentVariables</key>\n    <dict>\n{}    </dict>\n",
+            entries
+        )
+    };
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{label}</string>
+    <key>ProgramArguments</key>
+    <array>
+{args}    </array>
+    <key>WorkingDirectory</key>
+    <string>{cwd}</string>
+{env}    <key>RunAtLoad</key>
+    <{run_at_load}/>
+    <key>KeepAlive</key>
+    <{keep_alive}/>
+</dict>
+</plist>
+"#,
+        label = xml_escape(label),
+        args = args,
+        cwd = xml_escape(&project_path.to_string_lossy()),
+        env = env,
+        run_at_load = process.autostart,
+        keep_alive = process.autorestart,
+    )
+}
+
+/// Quotes a single argument for a systemd `ExecStart=` line.
+fn systemd_quote(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('%', "%%")
+        .replace('$', "$$");
+    format!("\"{}\"", escaped)
+}
+
+fn systemd_unit(
+    project_name: &str,
+    shell: &str,
+    project_path: &Path,
+    process: &ProcessConfig,
+) -> String {
+    // `Environment=` expands specifiers but not `$`, unlike `ExecStart=`.
+    let env = service_env(process)
+        .iter()
+        .map(|(key, value)| {
+            let assignment = format!("{}={}", key, value)
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('%', "%%");
+            format!("Environment=\"{}\"\n", assignment)
+        })
+        .collect::<String>();
+    format!(
+        "[Unit]\n\
+         Description=myterm {project} {process}\n\
+         \n\
+         [Service]\n\
+         WorkingDirectory={cwd}\n\
+         {env}\
+         ExecStart={shell} -lc {command}\n\
+         Restart={restart}\n\
+         \n\
+         [Install]\n\
+         WantedBy=default.target\n",
+        project = project_name,
+        process = process.name,
+        cwd = project_path.display(),
+        env = env,
+        shell = shell,
+        command = systemd_quote(&process.command),
+        restart = if process.autorestart { "always" } else { "no" },
+    )
+}
+
+/// Checks that an extracted bundle contains the executable we expect to relaunch, i.e.
+/// `Contents/MacOS/<name of the running binary>`.
+fn validate_app_bundle(bundle: &Path) -> Result<(), String> {
+    let exe_path = env::current_exe().map_err(|err| err.to_string())?;
+    let exe_name = exe_path
+        .file_name()
+        .ok_or_else(|| "Could not determine executable name".to_string())?;
+    let binary = bundle.join("Contents").join("MacOS").join(exe_name);
+    if !binary.is_file() {
+        return Err(format!(
+            "Update bundle is incomplete: missing {}",
+            binary.display()
+        ));
+    }
+    Ok(())
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn load_project_config(
+    settings: State<SettingsStore>,
+    path: String,
+) -> Result<ProjectConfig, String> {
+    let project_path = Path::new(&path);
+    let mut config = read_project_config(project_path)?;
+    let disabled = settings.get().disabled_processes;
+    for process in &mut config.processes {
+        process.disabled = disabled.contains(&process_key(&path, &process.name));
+    }
+    Ok(config)
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ConfigDiagnostic {
+    severity: Severity,
+    /// The process the problem belongs to; `None` for file-level problems.
+    process_name: Option<String>,
+    message: String,
+}
+
+/// Checks a config for problems a plain parse doesn't catch: duplicate names, settings that
+/// would fail at start (schedules, regexes, signals, umask, rlimits, env files) and settings that
+/// contradict each other. Lints `contents` when given (e.g. unsaved editor text), otherwise
+/// the file on disk. An empty list means no problems.
+#[tauri::command(rename_all = "camelCase")]
+fn lint_config(path: String, contents: Option<String>) -> Result<Vec<ConfigDiagnostic>, String> {
+    let project_path = Path::new(&path);
+    let contents = match contents {
+        Some(contents) => contents,
+        None => {
+            let config_path = find_existing_config_path(project_path)
+                .ok_or_else(|| "Missing myterm.yml".to_string())?;
+            fs::read_to_string(&config_path)
+                .map_err(|err| format!("{} ({})", err, config_path.display()))?
+        }
+    };
+    let normalized = normalize_config_text(&contents);
+    let parsed = check_config_version(&normalized).and_then(|()| {
+        serde_yaml::from_str::<ProjectConfig>(&normalized).map_err(|err| err.to_string())
+    });
+    let config = match parsed {
+        Ok(config) => config,
+        Err(message) => {
+            return Ok(vec![ConfigDiagnostic {
+                severity: Severity::Error,
+                process_name: None,
+                message,
+            }])
+        }
+    };
+
+    let mut diagnostics: Vec<ConfigDiagnostic> = config
+        .unknown
+        .keys()
+        .map(|key| ConfigDiagnostic {
+            severity: Severity::Warning,
+            process_name: None,
+            message: format!("Unknown key {}", key),
+        })
+        .collect();
+    if config.log_compress && config.log_max_bytes.is_none() {
+        diagnostics.push(ConfigDiagnostic {
+            severity: Severity::Warning,
+            process_name: None,
+            message: "log_compress has no effect without log_max_bytes".to_string(),
+        });
+    }
+    if config.log_max_bytes == Some(0) {
+        diagnostics.push(ConfigDiagnostic {
+            severity: Severity::Error,
+            process_name: None,
+            message: "log_max_bytes must be greater than 0".to_string(),
+        });
+    }
+    if config.log_format == LogFormat::Jsonl && config.log_prefix_format.is_some() {
+        diagnostics.push(ConfigDiagnostic {
+            severity: Severity::Warning,
+            process_name: None,
+            message: "log_prefix_format is ignored when log_format is jsonl".to_string(),
+        });
+    }
+    let working_dir = config.working_dir(project_path);
+    if config.root.is_some() && !working_dir.is_dir() {
+        diagnostics.push(ConfigDiagnostic {
+            severity: Severity::Error,
+            process_name: None,
+            message: format!("root not found: {}", working_dir.display()),
+        });
+    }
+    diagnostics.extend(lint_processes(&working_dir, &config.processes));
+    Ok(diagnostics)
+}
+
+/// `working_dir` is where the processes run, which relative paths are resolved against.
+fn lint_processes(working_dir: &Path, processes: &[ProcessConfig]) -> Vec<ConfigDiagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut names = HashSet::new();
+    let mut ports: HashMap<u16, &str> = HashMap::new();
+    // Lowercased tag -> first spelling seen, to spot `Frontend` vs `frontend`.
+    let mut tag_spellings: HashMap<String, &str> = HashMap::new();
+    let all_names: HashSet<&str> = processes.iter().map(|p| p.name.as_str()).collect();
+
+    for process in processes {
+        let mut report = |severity, message: String| {
+            diagnostics.push(ConfigDiagnostic {
+                severity,
+                process_name: Some(process.name.clone()),
+                message,
+            });
+        };
+
+        for key in process.unknown.keys() {
+            report(Severity::Warning, format!("Unknown key {}", key));
+        }
+        if process.name.trim().is_empty() {
+            report(Severity::Error, "Process has no name".to_string());
+        } else if !names.insert(process.name.as_str()) {
+            report(
+                Severity::Error,
+                "Another process has the same name".to_string(),
+            );
+        }
+        if let Err(err) = validate_command(&process.name, &process.command) {
+            report(Severity::Error, err);
+        }
+        if let Err(err) = validate_stop_sequence(process) {
+            report(Severity::Error, err);
+        }
+
+        let mut own_tags = HashSet::new();
+        for tag in &process.tags {
+            if tag.trim().is_empty() {
+                report(Severity::Warning, "Empty tag".to_string());
+                continue;
+            }
+            if !own_tags.insert(tag.as_str()) {
+                report(Severity::Warning, format!("Tag {} is listed twice", tag));
+            }
+            let spelling = tag_spellings.entry(tag.to_lowercase()).or_insert(tag);
+            if spelling != tag {
+                report(
+                    Severity::Warning,
+                    format!("Tag {} differs only in case from {}", tag, spelling),
+                );
+            }
+        }
+
+        match &process.ready_command {
+            Some(command) if command.trim().is_empty() => {
+                report(Severity::Error, "ready_command is empty".to_string());
+            }
+            Some(_) if process.startup_grace_secs.is_some() => report(
+                Severity::Warning,
+                "startup_grace_secs is ignored when ready_command is set".to_string(),
+            ),
+            Some(_) => {}
+            None if process.ready_timeout_secs.is_some() => report(
+                Severity::Warning,
+                "ready_timeout_secs has no effect without ready_command".to_string(),
+            ),
+            None => {}
+        }
+        if process.ready_timeout_secs == Some(0) {
+            report(
+                Severity::Error,
+                "ready_timeout_secs must be greater than 0".to_string(),
+            );
+        }
+
+        if let Some(spec) = &process.schedule {
+            if let Err(err) = Schedule::parse(spec) {
+                report(Severity::Error, err);
+            }
+            if process.autorestart {
+                report(
+                    Severity::Warning,
+                    "autorestart is ignored for scheduled processes".to_string(),
+                );
+            }
+        }
+        if let Some(pattern) = &process.restart_on_log_regex {
+            if let Err(err) = Regex::new(pattern) {
+                report(
+                    Severity::Error,
+                    format!("Invalid restart_on_log_regex: {}", err),
+                );
+            }
+        }
+        #[cfg(unix)]
+        if let Some(signal) = &process.reload_signal {
+            if let Err(err) = parse_signal(signal) {
+                report(Severity::Error, err);
+            }
+        }
+        if let Some(Err(err)) = process.umask.as_deref().map(parse_umask) {
+            report(Severity::Error, err);
+        }
+        if let Err(err) = process.rlimits.validate() {
+            report(Severity::Error, err);
+        }
+        for dependency in &process.depends_on {
+            if !all_names.contains(dependency.as_str()) {
+                report(
+                    Severity::Error,
+                    format!("depends_on names unknown process {}", dependency),
+                );
+            }
+        }
+        let process_dir = process.dir_in(working_dir);
+        if process.cwd.is_some() && !process_dir.is_dir() {
+            report(
+                Severity::Error,
+                format!("cwd not found: {}", process_dir.display()),
+            );
+        }
+        for file in &process.env_from_file {
+            if !process_dir.join(file).is_file() {
+                report(
+                    Severity::Error,
+                    format!("env_from_file not found: {}", file),
+                );
+            }
+        }
+        if let Some(port) = process.port {
+            if let Some(other) = ports.insert(port, &process.name) {
+                report(
+                    Severity::Warning,
+                    format!("Port {} is also declared by {}", port, other),
+                );
+            }
+        }
+    }
+    if let Err(err) = dependency_order(processes) {
+        diagnostics.push(ConfigDiagnostic {
+            severity: Severity::Error,
+            process_name: None,
+            message: err,
+        });
+    }
+
+    diagnostics
+}
+
+/// Only the top-level `name` of a config; the rest of the document is skipped.
+#[derive(Deserialize)]
+struct ProjectNameOnly {
+    #[serde(default)]
+    name: Option<String>,
+}
+
+/// The project's display name, without requiring the process entries to be valid. Falls back
+/// to the directory name when the config is missing, unreadable or has no `name`.
+#[tauri::command(rename_all = "camelCase")]
+fn get_project_name(path: String) -> String {
+    let project_path = Path::new(&path);
+    find_existing_config_path(project_path)
+        .and_then(|config_path| fs::read_to_string(config_path).ok())
+        .and_then(|contents| {
+            serde_yaml::from_str::<ProjectNameOnly>(&normalize_config_text(&contents)).ok()
+        })
+        .and_then(|config| config.name)
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| detect_project_name(project_path))
+}
+
+/// How many parent directories `search_parents` looks through.
+const MAX_CONFIG_SEARCH_DEPTH: usize = 16;
+
+/// With `search_parents`, directories above `path` are checked too (nearest first), stopping
+/// after the repository root (the first directory containing `.git`) or the filesystem root.
+#[tauri::command(rename_all = "camelCase")]
+fn get_config_candidates(
+    path: String,
+    search_parents: Option<bool>,
+) -> Result<ConfigCandidates, String> {
+    let project_path = std::path::absolute(&path).map_err(|err| format!("{} ({})", err, path))?;
+    let dirs: Vec<&Path> = if search_parents.unwrap_or(false) {
+        let ancestors = project_path.ancestors().take(MAX_CONFIG_SEARCH_DEPTH + 1);
+        let mut dirs = Vec::new();
+        for dir in ancestors {
+            dirs.push(dir);
+            if dir.join(".git").exists() {
+                break;
+            }
+        }
+        dirs
+    } else {
+        vec![project_path.as_path()]
+    };
+
+    let candidates: Vec<(&Path, PathBuf)> = dirs
+        .into_iter()
+        .flat_map(|dir| {
+            config_path_candidates(dir)
+                .into_iter()
+                .map(move |c| (dir, c))
+        })
+        .collect();
+    let found = candidates.iter().find(|(_, candidate)| candidate.exists());
+
+    Ok(ConfigCandidates {
+        candidates: candidates
+            .iter()
+            .map(|(_, candidate)| candidate.to_string_lossy().to_string())
+            .collect(),
+        existing: found.map(|(_, candidate)| candidate.to_string_lossy().to_string()),
+        project_dir: found.map(|(dir, _)| dir.to_string_lossy().to_string()),
+    })
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn init_project_config(path: String) -> Result<ProjectConfig, String> {
+    let project_path = Path::new(&path);
+    let config_path = project_path.join("myterm.yml");
+    if config_path.exists() {
+        return Err(format!("Config already exists at {}", config_path.display()));
+    }
+
+    let config = ProjectConfig {
+        version: Some(CONFIG_VERSION),
+        name: detect_project_name(project_path),
+        actions: Vec::new(),
+        processes: guess_processes(project_path),
+        root: None,
+        env: BTreeMap::new(),
+        log_dir: None,
+        log_file_mode: LogFileMode::default(),
+        log_prefix_format: None,
+        log_format: LogFormat::default(),
+        log_max_bytes: None,
+        log_compress: false,
+        strip_ansi: false,
+        unknown: BTreeMap::new(),
+    };
+
+    let yaml = serde_yaml::to_string(&config).map_err(|err| err.to_string())?;
+    std::fs::write(&config_path, yaml)
+        .map_err(|err| format!("{} ({})", err, config_path.display()))?;
+
+    Ok(config)
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn read_project_config_file(path: String) -> Result<ConfigFilePayload, String> {
+    let project_path = Path::new(&path);
+    let config_path = find_existing_config_path(project_path)
+        .ok_or_else(|| "Missing myterm.yml".to_string())?;
+    let contents = fs::read_to_string(&config_path)
+        .map_err(|err| format!("{} ({})", err, config_path.display()))?;
+    Ok(ConfigFilePayload {
+        path: config_path.to_string_lossy().to_string(),
+        contents,
+    })
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn write_project_config_file(path: String, contents: String) -> Result<(), String> {
+    let project_path = Path::new(&path);
+    let config_path = find_existing_config_path(project_path)
+        .unwrap_or_else(|| project_path.join("myterm.yml"));
+    write_config_text(&config_path, contents)
+}
+
+fn write_config_text(config_path: &Path, contents: String) -> Result<(), String> {
+    // Keep CRLF files CRLF so saving from the editor doesn't rewrite every line.
+    let uses_crlf = fs::read_to_string(config_path)
+        .map(|existing| existing.contains("\r\n"))
+        .unwrap_or(false);
+    let contents = if uses_crlf {
+        contents.replace("\r\n", "\n").replace('\n', "\r\n")
+    } else {
+        contents
+    };
+
+    fs::write(config_path, contents)
+        .map_err(|err| format!("{} ({})", err, config_path.display()))?;
+    Ok(())
+}
+
+/// Flips one process's `autostart` in the config file, editing just that line so comments and
+/// formatting survive. Returns the config as re-read from the result.
+#[tauri::command(rename_all = "camelCase")]
+fn set_autostart(
+    project_path: String,
+    process_name: String,
+    autostart: bool,
+) -> Result<ProjectConfig, String> {
+    let project_path = Path::new(&project_path);
+    let config_path = find_existing_config_path(project_path)
+        .ok_or_else(|| "Missing myterm.yml".to_string())?;
+    let raw = fs::read_to_string(&config_path)
+        .map_err(|err| format!("{} ({})", err, config_path.display()))?;
+    let value = if autostart { "true" } else { "false" };
+    let updated = set_process_field(
+        &normalize_config_text(&raw),
+        &process_name,
+        "autostart",
+        value,
+    )?;
+
+    // Never write an edit that doesn't read back as intended.
+    let config: ProjectConfig = serde_yaml::from_str(&updated)
+        .map_err(|err| format!("Could not update autostart in place: {}", err))?;
+    let applied = config
+        .processes
+        .iter()
+        .any(|process| process.name == process_name && process.autostart == autostart);
+    if !applied {
+        return Err("Could not update autostart in place".to_string());
+    }
+
+    // normalize_config_text dropped any BOM; keep the file's.
+    let contents = if raw.starts_with('\u{feff}') {
+        format!("\u{feff}{}", updated)
+    } else {
+        updated
+    };
+    write_config_text(&config_path, contents)?;
+    Ok(config)
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// Sets `key: value` on the named entry of a block-style `processes:` list, replacing the
+/// existing value (keeping any trailing comment) or adding the key after `name:`.
+fn set_process_field(
+    text: &str,
+    process_name: &str,
+    key: &str,
+    value: &str,
+) -> Result<String, String> {
+    let mut lines: Vec<String> = text.split('\n').map(str::to_string).collect();
+    let start = lines
+        .iter()
+        .position(|line| line.trim_end() == "processes:")
+        .ok_or_else(|| "Config has no block-style processes list".to_string())?;
+    let end = lines[start + 1..]
+        .iter()
+        .position(|line| indent_of(line) == 0 && !line.is_empty() && !line.starts_with(['-', '#']))
+        .map_or(lines.len(), |offset| start + 1 + offset);
+
+    // Column of the item's keys: after `- ` on the first line of the item.
+    let key_column = |line: &str| {
+        let trimmed = line.trim_start();
+        match trimmed.strip_prefix("- ") {
+            Some(after_dash) => line.len() - after_dash.trim_start().len(),
+            None => indent_of(line),
+        }
+    };
+    let (name_line, column) = (start + 1..end)
+        .find_map(|index| {
+            let line = &lines[index];
+            let column = key_column(line);
+            let entry = line[column..].strip_prefix("name:")?;
+            let entry = entry.split(" #").next().unwrap_or(entry).trim();
+            let name = ['"', '\'']
+                .iter()
+                .find_map(|quote| entry.strip_prefix(*quote)?.strip_suffix(*quote))
+                .unwrap_or(entry);
+            (name == process_name).then_some((index, column))
+        })
+        .ok_or_else(|| format!("Process {} not found in config", process_name))?;
+
+    let item_start = (start + 1..=name_line)
+        .rev()
+        .find(|index| {
+            let line = &lines[*index];
+            line.trim_start().starts_with("- ") && indent_of(line) < column
+        })
+        .unwrap_or(name_line);
+    let item_end = (name_line + 1..end)
+        .find(|index| {
+            let line = &lines[*index];
+            let trimmed = line.trim_start();
+            !trimmed.is_empty() && !trimmed.starts_with('#') && indent_of(line) < column
+        })
+        .unwrap_or(end);
+
+    let prefix = format!("{}:", key);
+    let existing = (item_start..item_end).find(|index| {
+        let line = &lines[*index];
+        key_column(line) == column && line[column..].starts_with(&prefix)
+    });
+    match existing {
+        Some(index) => {
+            let line = &lines[index];
+            let (head, rest) = line.split_at(column + prefix.len());
+            let (old_value, comment) = rest.split_at(rest.find(" #").unwrap_or(rest.len()));
+            let padding = &old_value[old_value.trim_end().len()..];
+            lines[index] = format!("{} {}{}{}", head, value, padding, comment);
+        }
+        None => {
+            let line = format!("{}{} {}", " ".repeat(column), prefix, value);
+            lines.insert(name_line + 1, line);
+        }
+    }
+    Ok(lines.join("\n"))
+}
+
+/// One-way export of the project's processes as user-level service definitions: `launchd`
+/// plists on macOS and `systemd --user` units elsewhere. Files are written to a fresh temp
+/// directory; installing them is left to the user.
+#[tauri::command(rename_all = "camelCase")]
+fn export_service_files(
+    settings: State<SettingsStore>,
+    path: String,
+) -> Result<ServiceExport, String> {
+    let project_path = Path::new(&path);
+    let config = read_project_config(project_path)?;
+    let shell = login_shell(&settings.get());
+    let project_slug = service_slug(&config.name);
+    let working_dir = config.working_dir(project_path);
+    let dir = create_temp_dir("myterm-services")?;
+
+    let mut files = Vec::new();
+    for process in &config.processes {
+        let process_slug = service_slug(&process.name);
+        let (file_name, contents) = if cfg!(target_os = "macos") {
+            let label = format!("com.myterm.{}.{}", project_slug, process_slug);
+            let plist = launchd_plist(&label, &shell, &process.dir_in(&working_dir), process);
+            (format!("{}.plist", label), plist)
+        } else {
+            let unit = systemd_unit(&config.name, &shell, &process.dir_in(&working_dir), process);
+            let file_name = format!("myterm-{}-{}.service", project_slug, process_slug);
+            (file_name, unit)
+        };
+
+        let file_path = dir.join(file_name);
+        fs::write(&file_path, contents)
+            .map_err(|err| format!("{} ({})", err, file_path.display()))?;
+        files.push(file_path.to_string_lossy().to_string());
+    }
+
+    Ok(ServiceExport {
+        dir: dir.to_string_lossy().to_string(),
+        files,
+    })
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn start_process(
+    app: AppHandle,
+    state: State<ProcessManager>,
+    settings: State<SettingsStore>,
+    project_path: String,
+    process_name: String,
+    command: String,
+    autorestart: bool,
+) -> Result<(), String> {
+    let settings = settings.get();
+    let mut process = process_to_start(&settings, &project_path, process_name, command)?;
+    process.autorestart = autorestart;
+    start_configured(app, state.inner(), &settings, project_path, process)
+}
+
+/// The config to start `process_name` with: the UI passes the command, everything else comes
+/// from the config file.
+fn process_to_start(
+    settings: &AppSettings,
+    project_path: &str,
+    process_name: String,
+    command: String,
+) -> Result<ProcessConfig, String> {
+    validate_command(&process_name, &command)?;
+    if settings
+        .disabled_processes
+        .contains(&process_key(project_path, &process_name))
+    {
+        return Err(format!("{} is disabled", process_name));
+    }
+    let configured = configured_process(Path::new(project_path), &process_name)?;
+    let mut process = configured.unwrap_or_else(|| ProcessConfig {
+        name: process_name,
+        ..Default::default()
+    });
+    process.command = command;
+    Ok(process)
+}
+
+fn start_configured(
+    app: AppHandle,
+    manager: &ProcessManager,
+    settings: &AppSettings,
+    project_path: String,
+    process: ProcessConfig,
+) -> Result<(), String> {
+    let manager = manager.clone();
+    check_process_limit(&manager, settings, &process)?;
+    let shell = login_shell(settings);
+    spawn_supervisor(EventSink::App(app), manager, shell, project_path, process)
+}
+
+/// Output lines `run_once` returns.
+const RUN_ONCE_TAIL_LINES: usize = 200;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RunOnceResult {
+    /// `None` when the process was terminated by a signal or never started.
+    exit_code: Option<i32>,
+    /// The last `RUN_ONCE_TAIL_LINES` lines of this run, including myterm's own messages.
+    output: Vec<LogEvent>,
+}
+
+/// Runs a process to completion without autorestart (or its schedule) for task-runner use such
+/// as lint, test or build. Output streams to the UI as usual.
+#[tauri::command(rename_all = "camelCase", async)]
+fn run_once(
+    app: AppHandle,
+    state: State<ProcessManager>,
+    settings: State<SettingsStore>,
+    project_path: String,
+    process_name: String,
+    command: String,
+) -> Result<RunOnceResult, String> {
+    let manager = state.inner();
+    let settings = settings.get();
+    let key = process_key(&project_path, &process_name);
+    let mut process = process_to_start(&settings, &project_path, process_name, command)?;
+    process.autorestart = false;
+    process.schedule = None;
+    process.run_once = true;
+
+    let started_ms = now_ms();
+    start_configured(app, manager, &settings, project_path, process)?;
+    while manager
+        .processes
+        .lock()
+        .map_err(|_| "Process map poisoned".to_string())?
+        .contains_key(&key)
+    {
+        thread::sleep(Duration::from_millis(200));
+    }
+
+    let exit_code = manager
+        .last_exits
+        .lock()
+        .map_err(|_| "Exit history poisoned".to_string())?
+        .get(&key)
+        .filter(|exit| exit.exited_at_ms >= started_ms)
+        .and_then(|exit| exit.code);
+    let mut output: Vec<LogEvent> = manager
+        .log_buffers
+        .lock()
+        .map_err(|_| "Log buffer poisoned".to_string())?
+        .get(&key)
+        .map(|buffer| {
+            buffer
+                .iter()
+                .filter(|event| event.timestamp_ms >= started_ms)
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default();
+    output.drain(..output.len().saturating_sub(RUN_ONCE_TAIL_LINES));
+    Ok(RunOnceResult { exit_code, output })
+}
+
+/// Tracks a process group started outside myterm so it can be watched and stopped from the UI.
+/// `pid` must be a process group leader. Nothing is captured, so attached processes have no logs.
+#[tauri::command(rename_all = "camelCase")]
+fn attach_pid(
+    app: AppHandle,
+    state: State<ProcessManager>,
+    project_path: String,
+    process_name: String,
+    pid: u32,
+) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        if !process_group_exists(pid) {
+            return Err(format!("No process group with id {}", pid));
+        }
+
+        let key = process_key(&project_path, &process_name);
+        let shared_name = SharedName::new(process_name.clone());
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        {
+            let mut map = state
+                .processes
+                .lock()
+                .map_err(|_| "Process map poisoned".to_string())?;
+            if map.contains_key(&key) {
+                return Err("Process already running".to_string());
+            }
+            map.insert(
+                key,
+                ManagedProcess {
+                    pid,
+                    project_path: project_path.clone(),
+                    process_name: shared_name.clone(),
+                    status: "running".to_string(),
+                    autorestart: Arc::new(AtomicBool::new(false)),
+                    one_shot: false,
+                    stop_flag: stop_flag.clone(),
+                    restart: None,
+                    stdin_mode: StdinMode::Raw,
+                    stdin: Arc::default(),
+                    recording: None,
+                    container: None,
+                    plan: None,
+                    started_at: Some(Instant::now()),
+                    restart_count: 0,
+                    stop_timeout: STOP_GRACE_PERIOD,
+                    stop_sequence: Vec::new(),
+                    #[cfg(windows)]
+                    job: None,
+                },
+            );
+        }
+
+        let sink = EventSink::App(app);
+        emit_status(&sink, &project_path, &process_name, "running", None);
+
+        let manager = state.inner().clone();
+        thread::spawn(move || {
+            while process_group_exists(pid) {
+                thread::sleep(Duration::from_secs(1));
+            }
+            let process_name = shared_name.get();
+            let key = process_key(&project_path, &process_name);
+            if release_attached(&manager, &key, &stop_flag) {
+                emit_status(&sink, &project_path, &process_name, "stopped", None);
+            }
+        });
+
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = (app, state, project_path, process_name, pid);
+        Err("Attaching to a running process is only supported on Unix".to_string())
+    }
+}
+
+/// Drops an attached process's entry once its group is gone, unless the key now belongs to
+/// another process (e.g. one started under the same name after this one was stopped). The
+/// entry is recognised by its `stop_flag`, which no other entry shares. Returns whether it
+/// was removed.
+#[cfg(unix)]
+fn release_attached(manager: &ProcessManager, key: &str, stop_flag: &Arc<AtomicBool>) -> bool {
+    let Ok(mut map) = manager.processes.lock() else {
+        return false;
+    };
+    let owned = map
+        .get(key)
+        .is_some_and(|entry| Arc::ptr_eq(&entry.stop_flag, stop_flag));
+    if owned {
+        map.remove(key);
+    }
+    owned
+}
+
+/// Exactly what `start_process` will execute for a process: the shell, its arguments, the
+/// working directory and the environment variables set on top of the inherited environment.
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct SpawnPlan {
+    shell: String,
+    args: Vec<String>,
+    cwd: PathBuf,
+    env: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    umask: Option<u32>,
+    #[serde(skip_serializing_if = "ResourceLimits::is_empty")]
+    rlimits: ResourceLimits,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    container: Option<ContainerHandle>,
+}
+
+impl SpawnPlan {
+    fn command(&self) -> Command {
+        let mut cmd = Command::new(&self.shell);
+        cmd.args(&self.args).current_dir(&self.cwd).envs(&self.env);
+        cmd
+    }
+}
+
+/// The container a process runs in, as named on `docker run`.
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ContainerHandle {
+    runtime: String,
+    name: String,
+    /// Login shell used to find `runtime`, as for the `run` itself.
+    #[serde(skip)]
+    shell: String,
+}
+
+impl ContainerHandle {
+    /// Asks the runtime to stop the container, SIGKILLing it after `grace`. The runtime CLI
+    /// we spawned may already be gone (e.g. SIGKILLed) while the container keeps running.
+    fn stop(&self, grace: Duration) {
+        let command = format!(
+            "{} stop -t {} {} >/dev/null 2>&1",
+            self.runtime,
+            grace.as_secs().max(1),
+            shell_quote(&self.name)
+        );
+        let _ = Command::new(&self.shell)
+            .arg("-lc")
+            .arg(command)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    }
+}
+
+/// Quotes a single argument for a POSIX shell command line.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Wraps `command` in `docker run` (or `podman run`) for `container`. The project is mounted
+/// at the container's working directory, the variables in `env` are passed through (except
+/// `PATH`, which belongs to the host), and stdin stays attached so input still works.
+fn container_command(
+    container: &ContainerConfig,
+    shell: &str,
+    project_path: &Path,
+    process_name: &str,
+    command: &str,
+    env: &BTreeMap<String, String>,
+) -> Result<(String, ContainerHandle), String> {
+    let runtime = container.runtime.as_deref().unwrap_or("docker");
+    if runtime != "docker" && runtime != "podman" {
+        return Err(format!(
+            "Unsupported container runtime {}: expected docker or podman",
+            runtime
+        ));
+    }
+    if container.image.trim().is_empty() {
+        return Err(format!("Process {} has no container image", process_name));
+    }
+    let workdir = container.workdir.as_deref().unwrap_or("/workspace");
+    let project_dir = project_path.file_name().map(|name| name.to_string_lossy());
+    let name = format!(
+        "myterm-{}-{}-{}",
+        service_slug(project_dir.as_deref().unwrap_or("project")),
+        service_slug(process_name),
+        std::process::id()
+    );
+
+    let mut args = vec![
+        format!("{} run --rm -i --name {}", runtime, shell_quote(&name)),
+        format!(
+            "-v {}",
+            shell_quote(&format!("{}:{}", project_path.display(), workdir))
+        ),
+    ];
+    for mount in &container.mounts {
+        let mount = if mount.starts_with('/') {
+            mount.clone()
+        } else {
+            format!("{}/{}", project_path.display(), mount)
+        };
+        args.push(format!("-v {}", shell_quote(&mount)));
+    }
+    args.push(format!("-w {}", shell_quote(workdir)));
+    for key in env.keys().filter(|key| key.as_str() != "PATH") {
+        args.push(format!("-e {}", shell_quote(key)));
+    }
+    args.push(shell_quote(&container.image));
+    args.push(format!("sh -c {}", shell_quote(command)));
+
+    let wrapped = format!(
+        "command -v {runtime} >/dev/null 2>&1 || {{ echo \"myterm: {runtime} not found in PATH\" >&2; exit 127; }}; exec {}",
+        args.join(" "),
+        runtime = runtime
+    );
+    let handle = ContainerHandle {
+        runtime: runtime.to_string(),
+        name,
+        shell: shell.to_string(),
+    };
+    Ok((wrapped, handle))
+}
+
+/// Runs `command` in the running container `container` names, passing through the variables
+/// in `env` like `container_command`.
+fn container_exec_command(
+    container: &ContainerHandle,
+    command: &str,
+    env: &BTreeMap<String, String>,
+) -> String {
+    let mut args = vec![format!("{} exec", container.runtime)];
+    for key in env.keys().filter(|key| key.as_str() != "PATH") {
+        args.push(format!("-e {}", shell_quote(key)));
+    }
+    args.push(shell_quote(&container.name));
+    args.push(format!("sh -c {}", shell_quote(command)));
+    format!("exec {}", args.join(" "))
+}
+
+/// Shell snippet that switches to the toolchain pinned in `cwd`, for `respect_version_managers`.
+/// Each step is skipped when its tool isn't installed, so the command still runs.
+fn version_manager_prelude(cwd: &Path) -> Option<String> {
+    let mut steps = Vec::new();
+    if cwd.join(".nvmrc").is_file() || cwd.join(".node-version").is_file() {
+        steps.push(
+            "if command -v fnm >/dev/null 2>&1; then eval \"$(fnm env)\" && fnm use --silent-if-unchanged; \
+             else export NVM_DIR=\"${NVM_DIR:-$HOME/.nvm}\"; \
+             [ -s \"$NVM_DIR/nvm.sh\" ] && . \"$NVM_DIR/nvm.sh\" && nvm use --silent; fi",
+        );
+    }
+    if cwd.join(".tool-versions").is_file() {
+        steps.push(
+            "[ -s \"${ASDF_DIR:-$HOME/.asdf}/asdf.sh\" ] && . \"${ASDF_DIR:-$HOME/.asdf}/asdf.sh\"",
+        );
+    }
+    (!steps.is_empty()).then(|| steps.join("; "))
+}
+
+fn plan_spawn(
+    shell: &str,
+    project_path: &Path,
+    process: &ProcessConfig,
+) -> Result<SpawnPlan, String> {
+    let cwd = match &process.project_root {
+        Some(root) if !root.is_dir() => {
+            return Err(format!("Project root not found: {}", root.display()));
+        }
+        Some(root) => process.dir_in(root),
+        None => process.dir_in(project_path),
+    };
+    if process.cwd.is_some() && !cwd.is_dir() {
+        return Err(format!("Working directory not found: {}", cwd.display()));
+    }
+    let mut env = process.project_env.clone();
+    for file in &process.env_from_file {
+        env.extend(read_env_file(&cwd.join(file))?);
+    }
+    env.extend(process.env.clone());
+    let base_path = env.get("PATH").map(String::as_str);
+    if let Some(search_path) = prepended_search_path(&cwd, &process.path_prepend, base_path)? {
+        env.insert("PATH".to_string(), search_path);
+    }
+
+    let command = expand_env_vars(&process.command, |name| {
+        env.get(name).cloned().or_else(|| std::env::var(name).ok())
+    });
+
+    let umask = process.umask.as_deref().map(parse_umask).transpose()?;
+    process.rlimits.validate()?;
+    if cfg!(not(unix)) && (umask.is_some() || !process.rlimits.is_empty()) {
+        return Err("umask and rlimits are only supported on Unix".to_string());
+    }
+
+    let (command, container) = match &process.container {
+        // Version managers act on the host, so they don't apply inside the container.
+        Some(container) => {
+            let (command, handle) =
+                container_command(container, shell, &cwd, &process.name, &command, &env)?;
+            (command, Some(handle))
+        }
+        None => match version_manager_prelude(&cwd) {
+            Some(prelude) if process.respect_version_managers => {
+                (format!("{}; {}", prelude, command), None)
+            }
+            _ => (command, None),
+        },
+    };
+
+    Ok(SpawnPlan {
+        shell: shell.to_string(),
+        args: vec!["-ilc".to_string(), command],
+        cwd,
+        env,
+        umask,
+        rlimits: process.rlimits,
+        container,
+    })
+}
+
+/// The `ProcessConfig` the backend would use for a process, so the UI doesn't have to
+/// re-derive it from the raw file.
+#[tauri::command(rename_all = "camelCase")]
+fn get_resolved_process_config(
+    project_path: String,
+    process_name: String,
+) -> Result<ProcessConfig, String> {
+    resolve_process_config(Path::new(&project_path), &process_name)
+}
+
+/// Shows the spawn command line for a configured process without running it.
+#[tauri::command(rename_all = "camelCase")]
+fn resolve_spawn_command(
+    settings: State<SettingsStore>,
+    project_path: String,
+    process_name: String,
+) -> Result<SpawnPlan, String> {
+    let project_path = Path::new(&project_path);
+    let process = resolve_process_config(project_path, &process_name)?;
+    plan_spawn(&login_shell(&settings.get()), project_path, &process)
+}
+
+/// Pause between an exit and the next autorestart attempt, unless `restart_delay_secs` is set.
+const RESTART_DELAY: Duration = Duration::from_secs(1);
+
+/// A run at least this long resets the crash count `max_restarts` is checked against.
+const STABLE_RUN: Duration = Duration::from_secs(10);
+
+const READY_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+const DEFAULT_READY_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A process's `ready_command`, planned like the process itself.
+#[derive(Clone)]
+struct ReadyCheck {
+    plan: SpawnPlan,
+    timeout: Duration,
+}
+
+/// `ready_command` runs with the process's own working directory and environment. For a
+/// process with a `container` it runs inside that container: a second `run` would clash with
+/// the container's name, and the service may only be reachable from inside.
+fn plan_ready_check(
+    shell: &str,
+    project_path: &Path,
+    process: &ProcessConfig,
+) -> Result<Option<ReadyCheck>, String> {
+    let Some(command) = &process.ready_command else {
+        return Ok(None);
+    };
+    let check = ProcessConfig {
+        command: command.clone(),
+        container: None,
+        respect_version_managers: process.respect_version_managers && process.container.is_none(),
+        ..process.clone()
+    };
+    let mut plan = plan_spawn(shell, project_path, &check)?;
+    if process.container.is_some() {
+        let container = plan_spawn(shell, project_path, process)?
+            .container
+            .ok_or_else(|| format!("Process {} has no container", process.name))?;
+        plan.args[1] = container_exec_command(&container, &plan.args[1], &plan.env);
+    }
+    Ok(Some(ReadyCheck {
+        plan,
+        timeout: process
+            .ready_timeout_secs
+            .map_or(DEFAULT_READY_TIMEOUT, Duration::from_secs),
+    }))
+}
+
+/// Cap on the probe output `test_readiness_probe` returns, in bytes; the end is kept.
+const PROBE_OUTPUT_LIMIT: usize = 4096;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProbeResult {
+    ready: bool,
+    /// `None` when the probe couldn't be run or was killed by a signal.
+    exit_code: Option<i32>,
+    /// stdout followed by stderr, or the reason the probe couldn't be run.
+    output: String,
+    duration_ms: u64,
+}
+
+/// How often `run_probe` checks whether the probe has exited.
+const PROBE_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Runs a process's `ready_command` once and reports the outcome, without touching the
+/// process's status. Works whether or not the process is running.
+#[tauri::command(rename_all = "camelCase")]
+async fn test_readiness_probe(
+    settings: State<'_, SettingsStore>,
+    project_path: String,
+    process_name: String,
+) -> Result<ProbeResult, String> {
+    let project_path = Path::new(&project_path);
+    let process = resolve_process_config(project_path, &process_name)?;
+    let shell = login_shell(&settings.get());
+    let check = plan_ready_check(&shell, project_path, &process)?
+        .ok_or_else(|| format!("{} has no ready_command", process_name))?;
+    Ok(run_probe(&check))
+}
+
+/// Runs `check` once in its own process group (a job on Windows). A probe still running after
+/// `check.timeout` is killed along with anything it started, e.g. a `curl` that never gets a
+/// response.
+fn run_probe(check: &ReadyCheck) -> ProbeResult {
+    let started = Instant::now();
+    let mut cmd = check.plan.command();
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(err) => {
+            return ProbeResult {
+                ready: false,
+                exit_code: None,
+                output: format!("Failed to run ready_command: {}", err),
+                duration_ms: started.elapsed().as_millis() as u64,
+            }
+        }
+    };
+    #[cfg(windows)]
+    let job = JobObject::assign(&child).ok();
+
+    // Drain both pipes while waiting so a chatty probe can't block on a full pipe.
+    let read_all = |pipe: Option<Box<dyn Read + Send>>| {
+        thread::spawn(move || {
+            let mut bytes = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut bytes);
+            }
+            bytes
+        })
+    };
+    let stdout = read_all(child.stdout.take().map(|pipe| Box::new(pipe) as _));
+    let stderr = read_all(child.stderr.take().map(|pipe| Box::new(pipe) as _));
+
+    let deadline = started + check.timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) if Instant::now() < deadline => thread::sleep(PROBE_POLL_INTERVAL),
+            _ => {
+                #[cfg(unix)]
+                signal_process_group(child.id(), libc::SIGKILL);
+                #[cfg(windows)]
+                if let Some(job) = &job {
+                    job.terminate();
+                }
+                let _ = child.kill();
+                let _ = child.wait();
+                break None;
+            }
+        }
+    };
+    let duration_ms = started.elapsed().as_millis() as u64;
+
+    let mut bytes = stdout.join().unwrap_or_default();
+    bytes.extend(stderr.join().unwrap_or_default());
+    let Some(status) = status else {
+        return ProbeResult {
+            ready: false,
+            exit_code: None,
+            output: format!("timed out after {}s", check.timeout.as_secs()),
+            duration_ms,
+        };
+    };
+    let mut text = String::from_utf8_lossy(&bytes).into_owned();
+    if text.len() > PROBE_OUTPUT_LIMIT {
+        let mut start = text.len() - PROBE_OUTPUT_LIMIT;
+        while !text.is_char_boundary(start) {
+            start += 1;
+        }
+        text.drain(..start);
+    }
+    ProbeResult {
+        ready: status.success(),
+        exit_code: status.code(),
+        output: text,
+        duration_ms,
+    }
+}
+
+/// Whether something accepts connections on `port` on the loopback interface (IPv4 or IPv6).
+fn port_in_use(port: u16) -> bool {
+    let timeout = Duration::from_millis(200);
+    [
+        SocketAddr::from((Ipv4Addr::LOCALHOST, port)),
+        SocketAddr::from((Ipv6Addr::LOCALHOST, port)),
+    ]
+    .iter()
+    .any(|addr| TcpStream::connect_timeout(addr, timeout).is_ok())
+}
+
+/// PID of the process listening on `port`, via `lsof`.
+fn port_owner(port: u16) -> Option<u32> {
+    let output = Command::new("lsof")
+        .args(["-nP", &format!("-iTCP:{}", port), "-sTCP:LISTEN", "-t"])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.trim().parse().ok())
+}
+
+/// Sends SIGTERM to whatever listens on `port` so a process declaring it can start. Returns
+/// the pid that was signalled.
+#[tauri::command(rename_all = "camelCase")]
+fn kill_port_owner(port: u16) -> Result<u32, String> {
+    let pid = port_owner(port).ok_or_else(|| format!("No listener found on port {}", port))?;
+    #[cfg(unix)]
+    {
+        if unsafe { libc::kill(pid as i32, libc::SIGTERM) } != 0 {
+            return Err(format!(
+                "Failed to signal pid {}: {}",
+                pid,
+                std::io::Error::last_os_error()
+            ));
+        }
+    }
+    Ok(pid)
+}
+
+/// Runs a process's child instances for `spawn_supervisor`'s thread.
+#[derive(Clone)]
+struct Supervisor {
+    sink: EventSink,
+    manager: ProcessManager,
+    project_path: String,
+    process_name: SharedName,
+    plan: SpawnPlan,
+    ready_check: Option<ReadyCheck>,
+    /// End of the `startup_grace_secs` window, if any.
+    grace_until: Option<Instant>,
+    log_source: LogSource,
+    stop_flag: Arc<AtomicBool>,
+    /// Spawns so far; the current run's id.
+    run_id: Arc<AtomicU64>,
+    restart_delay: Duration,
+    max_restarts: Option<u32>,
+}
+
+impl Supervisor {
+    /// Current map key. Compute it while holding the process map lock so a concurrent rename
+    /// can't slip in between.
+    fn key(&self) -> String {
+        process_key(&self.project_path, &self.process_name.get())
+    }
+
+    fn report(&self, status: &str) {
+        if let Ok(mut map) = self.manager.processes.lock() {
+            if let Some(entry) = map.get_mut(&self.key()) {
+                entry.status = status.to_string();
+                entry.started_at = match status {
+                    "running" => entry.started_at.or_else(|| Some(Instant::now())),
+                    _ => None,
+                };
+            }
+        }
+        emit_status(
+            &self.sink,
+            &self.project_path,
+            &self.process_name.get(),
+            status,
+            self.current_run(),
+        );
+    }
+
+    /// Emits one of our own lifecycle messages on the "system" stream, separate from the
+    /// program's stdout/stderr.
+    fn log(&self, line: String) {
+        emit_log(
+            &self.sink,
+            &self.project_path,
+            &self.process_name.get(),
+            line,
+            "system",
+            self.current_run(),
+        );
+    }
+
+    fn count_restart(&self) {
+        if let Ok(mut map) = self.manager.processes.lock() {
+            if let Some(entry) = map.get_mut(&self.key()) {
+                entry.restart_count = entry.restart_count.saturating_add(1);
+            }
+        }
+    }
+
+    /// `None` until the first spawn.
+    fn current_run(&self) -> Option<u64> {
+        Some(self.run_id.load(Ordering::SeqCst)).filter(|run| *run > 0)
+    }
+
+    fn stopped(&self) -> bool {
+        self.stop_flag.load(Ordering::SeqCst)
+    }
+
+    /// Sleeps for `duration`, waking early if the process is stopped. Returns false if stopped.
+    fn sleep_unless_stopped(&self, duration: Duration) -> bool {
+        let deadline = Instant::now() + duration;
+        while Instant::now() < deadline {
+            if self.stopped() {
+                return false;
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            thread::sleep(remaining.min(Duration::from_millis(200)));
+        }
+        !self.stopped()
+    }
+
+    /// Polls `ready_command` until it succeeds (→ "running"), the child exits, or the timeout
+    /// passes (→ "crashed").
+    fn spawn_ready_check(&self, check: ReadyCheck, exited: Arc<AtomicBool>) {
+        let supervisor = self.clone();
+        thread::spawn(move || {
+            let deadline = Instant::now() + check.timeout;
+            loop {
+                if exited.load(Ordering::SeqCst) || supervisor.stopped() {
+                    return;
+                }
+                let ready = check
+                    .plan
+                    .command()
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .status()
+                    .is_ok_and(|status| status.success());
+                if exited.load(Ordering::SeqCst) || supervisor.stopped() {
+                    return;
+                }
+                if ready {
+                    supervisor.promote_to_running();
+                    return;
+                }
+                if Instant::now() >= deadline {
+                    supervisor.log(format!(
+                        "[ready] ready_command did not succeed within {}s",
+                        check.timeout.as_secs()
+                    ));
+                    supervisor.report("crashed");
+                    return;
+                }
+                thread::sleep(READY_CHECK_INTERVAL);
+            }
+        });
+    }
+
+    /// Reports "running" once the startup grace window ends, unless the child exits first.
+    fn spawn_grace_timer(&self, grace_until: Instant, exited: Arc<AtomicBool>) {
+        let supervisor = self.clone();
+        thread::spawn(move || {
+            let remaining = grace_until.saturating_duration_since(Instant::now());
+            if supervisor.sleep_unless_stopped(remaining) && !exited.load(Ordering::SeqCst) {
+                supervisor.promote_to_running();
+            }
+        });
+    }
+
+    /// Moves a "starting" process to "running"; any other status (e.g. a crash that raced
+    /// the check) is left alone.
+    fn promote_to_running(&self) {
+        let mut promoted = false;
+        if let Ok(mut map) = self.manager.processes.lock() {
+            if let Some(entry) = map.get_mut(&self.key()) {
+                if entry.status == "starting" {
+                    entry.status = "running".to_string();
+                    entry.started_at = Some(Instant::now());
+                    promoted = true;
+                }
+            }
+        }
+        if promoted {
+            emit_status(
+                &self.sink,
+                &self.project_path,
+                &self.process_name.get(),
+                "running",
+                self.current_run(),
+            );
+        }
+    }
+
+    /// Spawns one child and blocks until it exits. Returns `None` if it couldn't be spawned.
+    fn run_child(&self) -> Option<std::io::Result<ExitStatus>> {
+        let run_id = self.run_id.fetch_add(1, Ordering::SeqCst) + 1;
+        let mut cmd = self.plan.command();
+        cmd.stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            let umask = self.plan.umask;
+            let rlimits = self.plan.rlimits;
+            unsafe {
+                cmd.pre_exec(move || {
+                    // Put the shell in its own process group so we can stop the entire tree.
+                    // (setpgid(0, 0) => pgid = pid)
+                    libc::setpgid(0, 0);
+                    if let Some(mask) = umask {
+                        libc::umask(mask as libc::mode_t);
+                    }
+                    rlimits.apply()
+                });
+            }
+        }
+
+        let mut child = match cmd.spawn() {
+            Ok(child) => child,
+            Err(err) => {
+                self.log(format!("Failed to start: {}", err));
+                return None;
+            }
+        };
+
+        let pid = child.id();
+        // Windows has no pre_exec, so the job is joined right after spawn; anything the shell
+        // starts from then on joins it too.
+        #[cfg(windows)]
+        let job = match JobObject::assign(&child) {
+            Ok(job) => Some(Arc::new(job)),
+            Err(err) => {
+                self.log(format!("Couldn't create a job object: {}", err));
+                None
+            }
+        };
+        if let Ok(mut map) = self.manager.processes.lock() {
+            if let Some(entry) = map.get_mut(&self.key()) {
+                entry.pid = pid;
+                #[cfg(windows)]
+                {
+                    entry.job = job;
+                }
+                if let Some(stdin) = child.stdin.take() {
+                    if let Ok(mut handle) = entry.stdin.lock() {
+                        *handle = StdinPipe {
+                            pipe: Some(stdin),
+                            pending: Vec::new(),
+                        };
+                    }
+                }
+            }
+        }
+
+        let exited = Arc::new(AtomicBool::new(false));
+        let in_grace = self.grace_until.filter(|until| Instant::now() < *until);
+        match (&self.ready_check, in_grace) {
+            (Some(check), _) => {
+                self.report("starting");
+                self.spawn_ready_check(check.clone(), exited.clone());
+            }
+            (None, Some(until)) => {
+                self.report("starting");
+                self.spawn_grace_timer(until, exited.clone());
+            }
+            (None, None) => self.report("running"),
+        }
+
+        let source = LogSource {
+            run_id: Some(run_id),
+            ..self.log_source.clone()
+        };
+        let mut readers = Vec::new();
+        if let Some(stdout) = child.stdout.take() {
+            readers.push(spawn_log_reader(source.clone(), "stdout", stdout));
+        }
+
+        if let Some(stderr) = child.stderr.take() {
+            readers.push(spawn_log_reader(source, "stderr", stderr));
+        }
+
+        let status = wait_for_exit(&mut child);
+        drain_log_readers(readers);
+        exited.store(true, Ordering::SeqCst);
+
+        if let Ok(mut map) = self.manager.processes.lock() {
+            if let Ok(status) = &status {
+                let process_name = self.process_name.get();
+                let exit = LastExit {
+                    project_path: self.project_path.clone(),
+                    process_name: process_name.clone(),
+                    code: status.code(),
+                    exited_at_ms: now_ms(),
+                };
+                record_last_exit(
+                    &self.manager,
+                    &process_key(&self.project_path, &process_name),
+                    exit,
+                );
+            }
+            if let Some(entry) = map.get_mut(&self.key()) {
+                // The group is gone; don't let a later stop signal a recycled pid.
+                entry.pid = 0;
+                if let Ok(mut handle) = entry.stdin.lock() {
+                    *handle = StdinPipe::default();
+                }
+            }
+        }
+
+        match &status {
+            Ok(status) => {
+                if let Some(code) = status.code() {
+                    self.log(format!("[exit] code {}", code));
+                } else {
+                    self.log("[exit] terminated by signal".to_string());
+                }
+            }
+            Err(err) => {
+                self.log(format!("[exit] wait failed: {}", err));
+            }
+        }
+
+        Some(status)
+    }
+
+    /// The always-on loop: run until stopped, restarting on exit while `autorestart` is set.
+    fn run_continuous(&self, autorestart: &AtomicBool, restart: &RestartControl) {
+        let mut first_run = true;
+        let mut crashes = 0;
+        loop {
+            if self.stopped() {
+                break;
+            }
+            if !first_run {
+                self.count_restart();
+            }
+            first_run = false;
+
+            let started = Instant::now();
+            if self.run_child().is_none() {
+                self.report("crashed");
+
+                if !autorestart.load(Ordering::SeqCst)
+                    || self.out_of_restarts(&mut crashes, started)
+                    || !self.wait_before_restart(self.restart_delay, restart)
+                {
+                    break;
+                }
+                continue;
+            }
+
+            if self.stopped() {
+                self.report("stopped");
+                break;
+            }
+
+            if restart.requested.swap(false, Ordering::SeqCst) {
+                // Requested restarts (restart_process, restart_on_log_regex) happen regardless
+                // of autorestart.
+                self.report("restarting");
+                if !self.wait_before_restart(self.restart_delay, restart) {
+                    break;
+                }
+                continue;
+            }
+
+            self.report("crashed");
+
+            if !autorestart.load(Ordering::SeqCst)
+                || self.out_of_restarts(&mut crashes, started)
+                || !self.wait_before_restart(self.restart_delay, restart)
+            {
+                break;
+            }
+        }
+    }
+
+    /// Counts a crash of the run begun at `started` against `max_restarts`; a run that lasted
+    /// `STABLE_RUN` starts the count over. Returns true, after logging it, once the limit is
+    /// exceeded.
+    fn out_of_restarts(&self, crashes: &mut u32, started: Instant) -> bool {
+        if started.elapsed() >= STABLE_RUN {
+            *crashes = 0;
+        }
+        *crashes += 1;
+        match self.max_restarts {
+            Some(max) if *crashes > max => {
+                self.log(format!("[restart] giving up after {} attempts", max));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Announces the pending restart, then waits `delay` unless `restart_now` cuts it short.
+    /// Returns false if stopped meanwhile.
+    fn wait_before_restart(&self, delay: Duration, restart: &RestartControl) -> bool {
+        self.sink.restart_pending(RestartPendingEvent {
+            project_path: self.project_path.clone(),
+            process_name: self.process_name.get(),
+            seconds: delay.as_millis().div_ceil(1000) as u64,
+        });
+        let deadline = Instant::now() + delay;
+        while Instant::now() < deadline {
+            if self.stopped() {
+                return false;
+            }
+            if restart.skip_wait.swap(false, Ordering::SeqCst) {
+                break;
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            thread::sleep(remaining.min(Duration::from_millis(200)));
+        }
+        // A late request shouldn't cut the next wait short.
+        restart.skip_wait.store(false, Ordering::SeqCst);
+        !self.stopped()
+    }
+
+    /// Runs the command at each tick of `schedule`. Runs never overlap: ticks that pass while
+    /// a run is still going are skipped.
+    fn run_scheduled(&self, schedule: &Schedule) {
+        while let Some(delay) = schedule.delay_until_next() {
+            self.report("scheduled");
+            if !self.sleep_unless_stopped(delay) {
+                break;
+            }
+
+            let status = self.run_child();
+            if self.stopped() {
+                break;
+            }
+            match status {
+                Some(Ok(status)) if status.success() => self.report("completed"),
+                _ => self.report("crashed"),
+            }
+        }
+        self.report("stopped");
+    }
+}
+
+/// When a scheduled process runs: `every 5m` style intervals or a cron expression.
+enum Schedule {
+    Every(Duration),
+    Cron(Box<cron::Schedule>),
+}
+
+impl Schedule {
+    fn parse(spec: &str) -> Result<Self, String> {
+        let spec = spec.trim();
+        if let Some(interval) = spec.strip_prefix("every ") {
+            return parse_interval(interval.trim())
+                .map(Schedule::Every)
+                .ok_or_else(|| format!("Invalid schedule interval: {}", spec));
+        }
+
+        // The cron crate wants a seconds field; accept classic five-field expressions too.
+        let expression = if spec.split_whitespace().count() == 5 {
+            format!("0 {}", spec)
+        } else {
+            spec.to_string()
+        };
+        cron::Schedule::from_str(&expression)
+            .map(|schedule| Schedule::Cron(Box::new(schedule)))
+            .map_err(|err| format!("Invalid cron schedule {}: {}", spec, err))
+    }
+
+    fn delay_until_next(&self) -> Option<Duration> {
+        match self {
+            Schedule::Every(interval) => Some(*interval),
+            Schedule::Cron(schedule) => {
+                let now = chrono::Local::now();
+                let next = schedule.after(&now).next()?;
+                (next - now).to_std().ok()
+            }
+        }
+    }
+}
+
+/// Parses `30s`, `5m`, `2h` or `1d`.
+fn parse_interval(value: &str) -> Option<Duration> {
+    let split = value.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = value.split_at(split);
+    let amount: u64 = amount.parse().ok()?;
+    let seconds = match unit.trim() {
+        "s" | "sec" | "secs" => amount,
+        "m" | "min" | "mins" => amount * 60,
+        "h" | "hr" | "hrs" => amount * 60 * 60,
+        "d" | "day" | "days" => amount * 60 * 60 * 24,
+        _ => return None,
+    };
+    (seconds > 0).then(|| Duration::from_secs(seconds))
+}
+
+/// Registers the process in the manager and spawns the thread that runs it (and restarts it
+/// when `autorestart` is set) until it is stopped.
+fn spawn_supervisor(
+    sink: EventSink,
+    manager: ProcessManager,
+    shell: String,
+    project_path: String,
+    process: ProcessConfig,
+) -> Result<(), String> {
+    let plan = plan_spawn(&shell, Path::new(&project_path), &process);
+    let ready_check = plan_ready_check(&shell, Path::new(&project_path), &process);
+    let one_shot = process.is_one_shot();
+    let ProcessConfig {
+        name: process_name,
+        autorestart,
+        detect_links,
+        level_colors,
+        restart_on_log_regex,
+        restart_on_log_stream,
+        schedule,
+        stdin_mode,
+        startup_grace_secs,
+        restart_delay_secs,
+        max_restarts,
+        stop_timeout_secs,
+        stop_sequence,
+        port,
+        record,
+        output_encoding,
+        log_file,
+        log_options,
+        strip_ansi,
+        ..
+    } = process;
+    let key = process_key(&project_path, &process_name);
+    let already_running = manager
+        .processes
+        .lock()
+        .map_err(|_| "Process map poisoned".to_string())?
+        .contains_key(&key);
+    if already_running {
+        return Err("Process already running".to_string());
+    }
+    // E.g. a missing `cwd`: show it in the process's log too, not only as the command's error.
+    let plan = plan.inspect_err(|err| {
+        emit_log(
+            &sink,
+            &project_path,
+            &process_name,
+            err.clone(),
+            "stderr",
+            None,
+        );
+        emit_status(&sink, &project_path, &process_name, "crashed", None);
+    })?;
+    let ready_check = ready_check?;
+    if let Some(port) = port.filter(|port| port_in_use(*port)) {
+        let pid = port_owner(port);
+        sink.port_in_use(PortInUseEvent {
+            project_path: project_path.clone(),
+            process_name: process_name.clone(),
+            port,
+            pid,
+        });
+        return Err(match pid {
+            Some(pid) => format!("Port {} is already in use by pid {}", port, pid),
+            None => format!("Port {} is already in use", port),
+        });
+    }
+    let shared_name = SharedName::new(process_name);
+    let schedule = schedule.as_deref().map(Schedule::parse).transpose()?;
+    acquire_project_lock(&manager, Path::new(&project_path))?;
+    let restart = RestartControl::default();
+    let restart_trigger = match restart_on_log_regex {
+        Some(pattern) => {
+            let regex = Regex::new(&pattern)
+                .map_err(|err| format!("Invalid restart_on_log_regex: {}", err))?;
+            Some(Arc::new(LogRestartTrigger {
+                regex,
+                streams: restart_on_log_stream,
+                manager: manager.clone(),
+                project_path: project_path.clone(),
+                process_name: shared_name.clone(),
+                last_fired: Mutex::new(None),
+            }))
+        }
+        None => None,
+    };
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let autorestart = Arc::new(AtomicBool::new(autorestart));
+    let (recording, log_file) = {
+        let mut map = manager
+            .processes
+            .lock()
+            .map_err(|_| "Process map poisoned".to_string())?;
+        if map.contains_key(&key) {
+            return Err("Process already running".to_string());
+        }
+        let recording = if record {
+            let recording = Recording::create(Path::new(&project_path), &shared_name.get())?;
+            Some(Arc::new(recording))
+        } else {
+            None
+        };
+        let log_file = match &log_file {
+            Some(path) => Some(Arc::new(ProcessLogFile::open(path, &log_options)?)),
+            None => None,
+        };
+        map.insert(
+            key.clone(),
+            ManagedProcess {
+                pid: 0,
+                project_path: project_path.clone(),
+                process_name: shared_name.clone(),
+                status: "starting".to_string(),
+                autorestart: autorestart.clone(),
+                one_shot,
+                stop_flag: stop_flag.clone(),
+                restart: schedule.is_none().then(|| restart.clone()),
+                stdin_mode,
+                stdin: Arc::default(),
+                recording: recording.clone(),
+                container: plan.container.clone(),
+                plan: Some(plan.clone()),
+                started_at: None,
+                restart_count: 0,
+                stop_timeout: stop_timeout_secs.map_or(STOP_GRACE_PERIOD, Duration::from_secs),
+                stop_sequence,
+                #[cfg(windows)]
+                job: None,
+            },
+        );
+        (recording, log_file)
+    };
+
+    thread::spawn(move || {
+        let log_source = LogSource {
+            sink: sink.clone(),
+            manager: manager.clone(),
+            project_path: project_path.clone(),
+            process_name: shared_name.clone(),
+            detect_links,
+            level_colors,
+            restart_trigger,
+            recording,
+            log_file,
+            encoding: output_encoding,
+            strip_ansi,
+            run_id: None,
+        };
+        let supervisor = Supervisor {
+            sink,
+            manager: manager.clone(),
+            project_path,
+            process_name: shared_name,
+            plan,
+            ready_check,
+            grace_until: startup_grace_secs.map(|secs| Instant::now() + Duration::from_secs(secs)),
+            log_source,
+            stop_flag,
+            run_id: Arc::default(),
+            restart_delay: restart_delay_secs.map_or(RESTART_DELAY, Duration::from_secs),
+            max_restarts,
+        };
+
+        if let Some(schedule) = schedule {
+            supervisor.run_scheduled(&schedule);
+        } else {
+            supervisor.run_continuous(&autorestart, &restart);
+        }
+
+        if let Ok(mut map) = manager.processes.lock() {
+            map.remove(&supervisor.key());
+        }
+    });
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProcessDrift {
+    process_name: String,
+    /// Which of `command`, `env`, `cwd` and `autorestart` differ.
+    fields: Vec<String>,
+}
+
+/// How the running processes differ from the config on disk.
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct ConfigDrift {
+    /// `autostart` processes in the config that aren't running.
+    added: Vec<String>,
+    /// Running processes the config no longer has.
+    removed: Vec<String>,
+    /// Running processes whose config changed since they were started.
+    changed: Vec<ProcessDrift>,
+    /// Running processes that match the config.
+    unchanged: Vec<String>,
+}
+
+/// Compares the config on disk with what's running for the project, without changing anything.
+/// Attached processes aren't part of the config and are left out.
+#[tauri::command(rename_all = "camelCase")]
+fn config_drift(
+    state: State<ProcessManager>,
+    settings: State<SettingsStore>,
+    project_path: String,
+) -> Result<ConfigDrift, String> {
+    let path = Path::new(&project_path);
+    let config = read_project_config(path)?;
+    let shell = login_shell(&settings.get());
+    let running: HashMap<String, (SpawnPlan, bool)> = {
+        let map = state
+            .processes
+            .lock()
+            .map_err(|_| "Process map poisoned".to_string())?;
+        map.values()
+            .filter(|entry| entry.project_path == project_path)
+            .filter_map(|entry| {
+                let plan = entry.plan.clone()?;
+                let autorestart = entry.autorestart.load(Ordering::SeqCst);
+                Some((entry.process_name.get(), (plan, autorestart)))
+            })
+            .collect()
+    };
+
+    let mut drift = ConfigDrift::default();
+    for process in &config.processes {
+        let Some((plan, autorestart)) = running.get(&process.name) else {
+            if process.autostart {
+                drift.added.push(process.name.clone());
+            }
+            continue;
+        };
+        let mut fields = Vec::new();
+        match plan_spawn(&shell, path, process) {
+            Ok(wanted) => {
+                if wanted.args != plan.args {
+                    fields.push("command".to_string());
+                }
+                if wanted.env != plan.env {
+                    fields.push("env".to_string());
+                }
+                if wanted.cwd != plan.cwd {
+                    fields.push("cwd".to_string());
+                }
+            }
+            // E.g. a missing env file: starting it again would fail, so it counts as changed.
+            Err(_) => fields.push("command".to_string()),
+        }
+        if process.autorestart != *autorestart {
+            fields.push("autorestart".to_string());
+        }
+        if fields.is_empty() {
+            drift.unchanged.push(process.name.clone());
+        } else {
+            drift.changed.push(ProcessDrift {
+                process_name: process.name.clone(),
+                fields,
+            });
+        }
+    }
+
+    let configured: HashSet<&str> = config.processes.iter().map(|p| p.name.as_str()).collect();
+    drift.removed = running
+        .into_keys()
+        .filter(|name| !configured.contains(name.as_str()))
+        .collect();
+    drift.removed.sort();
+    Ok(drift)
+}
+
+/// The managed processes (all, or one project's), sorted by project and name.
+fn process_snapshots(
+    manager: &ProcessManager,
+    project_path: Option<&str>,
+) -> Result<Vec<ProcessSnapshot>, String> {
+    let map = manager
+        .processes
+        .lock()
+        .map_err(|_| "Process map poisoned".to_string())?;
+    let mut processes: Vec<ProcessSnapshot> = map
+        .values()
+        .filter(|entry| project_path.is_none_or(|project| project == entry.project_path))
+        .map(|entry| ProcessSnapshot {
+            project_path: entry.project_path.clone(),
+            process_name: entry.process_name.get(),
+            pid: entry.pid,
+            status: entry.status.clone(),
+            autorestart: entry.autorestart.load(Ordering::SeqCst),
+        })
+        .collect();
+    processes.sort_by(|a, b| {
+        (&a.project_path, &a.process_name).cmp(&(&b.project_path, &b.process_name))
+    });
+    Ok(processes)
+}
+
+/// What's currently managed for a project, so a remounted view can catch up without waiting
+/// for `process-status` events.
+#[tauri::command(rename_all = "camelCase")]
+fn list_processes(
+    state: State<ProcessManager>,
+    project_path: String,
+) -> Result<Vec<ProcessSnapshot>, String> {
+    process_snapshots(&state, Some(&project_path))
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProcessStatusInfo {
+    process_name: String,
+    pid: u32,
+    status: String,
+    /// Seconds since the current run reached "running"; unset while it isn't running.
+    uptime_secs: Option<u64>,
+    restart_count: u32,
+    /// Whether the process is restarted when it exits; see `set_autorestart`.
+    autorestart: bool,
+}
+
+/// Status, uptime and restart count of one managed process.
+#[tauri::command(rename_all = "camelCase")]
+fn get_process_status(
+    state: State<ProcessManager>,
+    project_path: String,
+    process_name: String,
+) -> Result<ProcessStatusInfo, String> {
+    let map = state
+        .processes
+        .lock()
+        .map_err(|_| "Process map poisoned".to_string())?;
+    let Some(entry) = map.get(&process_key(&project_path, &process_name)) else {
+        return Err("Process not running".to_string());
+    };
+    Ok(ProcessStatusInfo {
+        process_name: entry.process_name.get(),
+        pid: entry.pid,
+        status: entry.status.clone(),
+        uptime_secs: entry.started_at.map(|started| started.elapsed().as_secs()),
+        restart_count: entry.restart_count,
+        autorestart: entry.autorestart.load(Ordering::SeqCst),
+    })
+}
+
+/// Turns autorestart on or off for a running process without restarting it, e.g. to let a
+/// flapping process stay down after its next crash. It lasts until the process is started
+/// again; the config file isn't changed. A service stays a service either way.
+#[tauri::command(rename_all = "camelCase")]
+fn set_autorestart(
+    state: State<ProcessManager>,
+    project_path: String,
+    process_name: String,
+    enabled: bool,
+) -> Result<(), String> {
+    let map = state
+        .processes
+        .lock()
+        .map_err(|_| "Process map poisoned".to_string())?;
+    let Some(entry) = map.get(&process_key(&project_path, &process_name)) else {
+        return Err("Process not running".to_string());
+    };
+    if entry.restart.is_none() {
+        return Err("Scheduled and attached processes don't autorestart".to_string());
+    }
+    entry.autorestart.store(enabled, Ordering::SeqCst);
+    Ok(())
+}
+
+/// Everything the UI needs to rehydrate after a reload, in one round-trip. Pass a
+/// `project_path` to limit the result to a single project.
+#[tauri::command(rename_all = "camelCase")]
+fn sync_state(
+    state: State<ProcessManager>,
+    settings: State<SettingsStore>,
+    project_path: Option<String>,
+) -> Result<SyncState, String> {
+    let wanted = project_path.as_deref();
+    let in_scope = |path: &str| wanted.is_none_or(|project| project == path);
+    let settings = settings.get();
+    if let Some(max_age) = settings.last_exit_max_age_secs {
+        prune_last_exits(&state, None, Some(Duration::from_secs(max_age)))?;
+    }
+
+    let processes = process_snapshots(&state, wanted)?;
+
+    let last_exits = {
+        let exits = state
+            .last_exits
+            .lock()
+            .map_err(|_| "Exit history poisoned".to_string())?;
+        exits
+            .values()
+            .filter(|exit| in_scope(&exit.project_path))
+            .cloned()
+            .collect()
+    };
+
+    Ok(SyncState {
+        processes,
+        last_exits,
+        settings,
+    })
+}
+
+/// Clears retained last-exit entries for a project (or all projects when `project_path` is
+/// unset), optionally only those older than `older_than_secs`. Returns the number removed.
+#[tauri::command(rename_all = "camelCase")]
+fn prune_stopped(
+    state: State<ProcessManager>,
+    project_path: Option<String>,
+    older_than_secs: Option<u64>,
+) -> Result<usize, String> {
+    prune_last_exits(
+        &state,
+        project_path.as_deref(),
+        older_than_secs.map(Duration::from_secs),
+    )
+}
+
+/// Recovery for a map entry whose supervisor is gone (e.g. it panicked), which would otherwise
+/// make every start fail with "Process already running". Refuses while the entry's process
+/// group is alive; use `stop_process` for that.
+#[tauri::command(rename_all = "camelCase")]
+fn force_clear_process(
+    app: AppHandle,
+    state: State<ProcessManager>,
+    project_path: String,
+    process_name: String,
+) -> Result<(), String> {
+    let key = process_key(&project_path, &process_name);
+    let entry = {
+        let mut map = state
+            .processes
+            .lock()
+            .map_err(|_| "Process map poisoned".to_string())?;
+        let Some(entry) = map.get(&key) else {
+            return Err("Process not running".to_string());
+        };
+        #[cfg(unix)]
+        let alive = process_group_exists(entry.pid);
+        // Without a way to probe the group, never assume it's gone.
+        #[cfg(not(unix))]
+        let alive = true;
+        if alive {
+            return Err(format!(
+                "Process group {} may still be running; stop it instead",
+                entry.pid
+            ));
+        }
+        map.remove(&key)
+    };
+    // A supervisor that is somehow still around must not respawn into the cleared slot.
+    if let Some(entry) = entry {
+        entry.stop_flag.store(true, Ordering::SeqCst);
+    }
+    emit_status(
+        &EventSink::App(app),
+        &project_path,
+        &process_name,
+        "stopped",
+        None,
+    );
+    Ok(())
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProcessDump {
+    key: String,
+    project_path: String,
+    process_name: String,
+    pid: u32,
+    status: String,
+    autorestart: bool,
+    one_shot: bool,
+    stop_flag: bool,
+    /// `None` when the process has no restart loop (scheduled or attached).
+    restart_requested: Option<bool>,
+    stdin_open: bool,
+    stdin_pending_bytes: usize,
+    group_exists: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StateDump {
+    processes: Vec<ProcessDump>,
+    last_exits: BTreeMap<String, LastExit>,
+    locked_projects: Vec<PathBuf>,
+    log_subscriptions: Vec<u64>,
+}
+
+/// Everything `ProcessManager` holds, plus whether each process group is really alive, for
+/// diagnosing UI/backend desyncs. Only available in debug builds or with `MYTERM_DEBUG` set.
+#[tauri::command(rename_all = "camelCase")]
+fn debug_dump_state(state: State<ProcessManager>) -> Result<StateDump, String> {
+    if !cfg!(debug_assertions) && env::var_os("MYTERM_DEBUG").is_none() {
+        return Err("debug_dump_state is disabled; set MYTERM_DEBUG=1 to enable it".to_string());
+    }
+
+    let mut processes: Vec<ProcessDump> = {
+        let map = state
+            .processes
+            .lock()
+            .map_err(|_| "Process map poisoned".to_string())?;
+        map.iter()
+            .map(|(key, entry)| {
+                let (stdin_open, stdin_pending_bytes) = entry
+                    .stdin
+                    .lock()
+                    .map(|stdin| (stdin.pipe.is_some(), stdin.pending.len()))
+                    .unwrap_or_default();
+                #[cfg(unix)]
+                let group_exists = process_group_exists(entry.pid);
+                #[cfg(not(unix))]
+                let group_exists = false;
+                ProcessDump {
+                    key: key.clone(),
+                    project_path: entry.project_path.clone(),
+                    process_name: entry.process_name.get(),
+                    pid: entry.pid,
+                    status: entry.status.clone(),
+                    autorestart: entry.autorestart.load(Ordering::SeqCst),
+                    one_shot: entry.one_shot,
+                    stop_flag: entry.stop_flag.load(Ordering::SeqCst),
+                    restart_requested: entry
+                        .restart
+                        .as_ref()
+                        .map(|restart| restart.requested.load(Ordering::SeqCst)),
+                    stdin_open,
+                    stdin_pending_bytes,
+                    group_exists,
+                }
+            })
+            .collect()
+    };
+    processes.sort_by(|a, b| a.key.cmp(&b.key));
+
+    let last_exits = state
+        .last_exits
+        .lock()
+        .map_err(|_| "Exit history poisoned".to_string())?
+        .iter()
+        .map(|(key, exit)| (key.clone(), exit.clone()))
+        .collect();
+    let mut locked_projects: Vec<PathBuf> = state
+        .locked_projects
+        .lock()
+        .map_err(|_| "Project locks poisoned".to_string())?
+        .iter()
+        .cloned()
+        .collect();
+    locked_projects.sort();
+    let mut log_subscriptions: Vec<u64> = state
+        .log_subscriptions
+        .lock()
+        .map_err(|_| "Log subscriptions poisoned".to_string())?
+        .keys()
+        .copied()
+        .collect();
+    log_subscriptions.sort();
+
+    Ok(StateDump {
+        processes,
+        last_exits,
+        locked_projects,
+        log_subscriptions,
+    })
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn stop_process(
+    state: State<ProcessManager>,
+    project_path: String,
+    process_name: String,
+    grace_ms: Option<u64>,
+    poll_ms: Option<u64>,
+) -> Result<(), String> {
+    let key = process_key(&project_path, &process_name);
+    let grace = grace_ms.map(Duration::from_millis);
+    let poll = poll_ms.map_or(SHUTDOWN_POLL_INTERVAL, Duration::from_millis);
+    stop_managed_process(state.inner(), key, grace, poll)
+}
+
+/// Stops a process (if running) and keeps it off: `start_process` refuses it and
+/// `load_project_config` reports it as `disabled` so autostart skips it, until `enable_process`.
+#[tauri::command(rename_all = "camelCase")]
+fn disable_process(
+    state: State<ProcessManager>,
+    settings: State<SettingsStore>,
+    project_path: String,
+    process_name: String,
+) -> Result<(), String> {
+    let key = process_key(&project_path, &process_name);
+    let mut updated = settings.get();
+    if updated.disabled_processes.insert(key.clone()) {
+        settings.save(updated)?;
+    }
+    match stop_managed_process(state.inner(), key, None, SHUTDOWN_POLL_INTERVAL) {
+        Ok(()) => Ok(()),
+        Err(err) if err == "Process not running" => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Undoes `disable_process`. Doesn't start the process.
+#[tauri::command(rename_all = "camelCase")]
+fn enable_process(
+    settings: State<SettingsStore>,
+    project_path: String,
+    process_name: String,
+) -> Result<(), String> {
+    let mut updated = settings.get();
+    if updated
+        .disabled_processes
+        .remove(&process_key(&project_path, &process_name))
+    {
+        settings.save(updated)?;
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Clone)]
+struct RenameEvent {
+    project_path: String,
+    old_name: String,
+    new_name: String,
+}
+
+/// Relabels a running process without restarting it. Later log and status events, plus its
+/// last exit, use the new name.
+#[tauri::command(rename_all = "camelCase")]
+fn rename_process(
+    app: AppHandle,
+    state: State<ProcessManager>,
+    project_path: String,
+    old_name: String,
+    new_name: String,
+) -> Result<(), String> {
+    let new_name = new_name.trim().to_string();
+    if new_name.is_empty() {
+        return Err("Process name cannot be empty".to_string());
+    }
+    if new_name == old_name {
+        return Ok(());
+    }
+
+    let old_key = process_key(&project_path, &old_name);
+    let new_key = process_key(&project_path, &new_name);
+    {
+        let mut map = state
+            .processes
+            .lock()
+            .map_err(|_| "Process map poisoned".to_string())?;
+        if map.contains_key(&new_key) {
+            return Err(format!("A process named {} is already running", new_name));
+        }
+        let Some(entry) = map.remove(&old_key) else {
+            return Err("Process not running".to_string());
+        };
+        entry.process_name.set(new_name.clone());
+        map.insert(new_key.clone(), entry);
+
+        // Still under the map lock, so the supervisor can't record an exit under the old key.
+        if let Ok(mut exits) = state.last_exits.lock() {
+            if let Some(mut exit) = exits.remove(&old_key) {
+                exit.process_name = new_name.clone();
+                exits.insert(new_key.clone(), exit);
+            }
+        }
+        if let Ok(mut subscriptions) = state.log_subscriptions.lock() {
+            for subscription in subscriptions.values_mut() {
+                if subscription.key == old_key {
+                    subscription.key = new_key.clone();
+                }
+            }
+        }
+        if let Ok(mut buffers) = state.log_buffers.lock() {
+            if let Some(mut buffer) = buffers.remove(&old_key) {
+                for event in buffer.iter_mut() {
+                    event.process_name = new_name.clone();
+                }
+                buffers.insert(new_key.clone(), buffer);
+            }
+        }
+    }
+
+    let _ = app.emit(
+        "process-renamed",
+        RenameEvent {
+            project_path,
+            old_name,
+            new_name,
+        },
+    );
+    Ok(())
+}
+
+/// The process's most recent output lines (up to `limit`, else all that are buffered), oldest
+/// first, for a view that wasn't listening to `process-log` when they were emitted. The lines
+/// outlive the process, so a crash's output can still be read.
+#[tauri::command(rename_all = "camelCase")]
+fn get_logs(
+    state: State<ProcessManager>,
+    project_path: String,
+    process_name: String,
+    limit: Option<usize>,
+) -> Result<Vec<LogEvent>, String> {
+    let buffers = state
+        .log_buffers
+        .lock()
+        .map_err(|_| "Log buffer poisoned".to_string())?;
+    let Some(buffer) = buffers.get(&process_key(&project_path, &process_name)) else {
+        return Ok(Vec::new());
+    };
+    let skip = limit.map_or(0, |limit| buffer.len().saturating_sub(limit));
+    Ok(buffer.iter().skip(skip).cloned().collect())
+}
+
+/// The process's file in the project's `log_dir`.
+fn log_file_path(project_path: &str, process_name: &str) -> Result<PathBuf, String> {
+    resolve_process_config(Path::new(project_path), process_name)?
+        .log_file
+        .ok_or_else(|| format!("Process {} has no log file; set log_dir", process_name))
+}
+
+/// The process's log file lines, oldest first: the last `limit` (else all), read back through
+/// rotated and compressed segments as far as needed. Unlike `get_logs` this reaches earlier
+/// app sessions.
+#[tauri::command(rename_all = "camelCase")]
+async fn get_log_history(
+    project_path: String,
+    process_name: String,
+    limit: Option<usize>,
+) -> Result<Vec<String>, String> {
+    let path = log_file_path(&project_path, &process_name)?;
+    let mut segments = Vec::new();
+    let mut count = 0;
+    for segment in log_segments(&path).iter().rev() {
+        let lines = read_log_segment(segment)?;
+        count += lines.len();
+        segments.push(lines);
+        if limit.is_some_and(|limit| count >= limit) {
+            break;
+        }
+    }
+    let mut lines: Vec<String> = segments.into_iter().rev().flatten().collect();
+    let skip = limit.map_or(0, |limit| lines.len().saturating_sub(limit));
+    lines.drain(..skip);
+    Ok(lines)
+}
+
+/// Most lines `search_logs` returns.
+const LOG_SEARCH_MATCHES: usize = 1000;
+
+/// Log file lines containing `query` (ignoring case) across all segments, oldest first. Only
+/// the newest `limit` matches are kept, never more than `LOG_SEARCH_MATCHES`.
+#[tauri::command(rename_all = "camelCase")]
+async fn search_logs(
+    project_path: String,
+    process_name: String,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<String>, String> {
+    let path = log_file_path(&project_path, &process_name)?;
+    let limit = limit.map_or(LOG_SEARCH_MATCHES, |limit| limit.min(LOG_SEARCH_MATCHES));
+    let query = query.to_lowercase();
+    let mut matches = VecDeque::new();
+    for segment in log_segments(&path) {
+        for line in read_log_segment(&segment)? {
+            if !line.to_lowercase().contains(&query) {
+                continue;
+            }
+            if matches.len() == limit {
+                matches.pop_front();
+            }
+            matches.push_back(line);
+        }
+    }
+    Ok(matches.into())
+}
+
+/// Most lines `get_merged_logs` returns.
+const MERGED_LOG_LINES: usize = 5000;
+
+/// Buffered output of several processes (`process_names`, or the whole project) in one stream
+/// ordered by `timestamp_ms`, each line still naming its process. `since_ms`/`until_ms` bound
+/// the time range; the newest `limit` lines are kept, never more than `MERGED_LOG_LINES`.
+#[tauri::command(rename_all = "camelCase")]
+fn get_merged_logs(
+    state: State<ProcessManager>,
+    project_path: String,
+    process_names: Option<Vec<String>>,
+    since_ms: Option<u64>,
+    until_ms: Option<u64>,
+    limit: Option<usize>,
+) -> Result<Vec<LogEvent>, String> {
+    merged_logs(
+        state.inner(),
+        &project_path,
+        process_names.as_deref(),
+        since_ms.map_or(0, u128::from),
+        until_ms.map_or(u128::MAX, u128::from),
+        limit.map_or(MERGED_LOG_LINES, |limit| limit.min(MERGED_LOG_LINES)),
+    )
+}
+
+fn merged_logs(
+    manager: &ProcessManager,
+    project_path: &str,
+    process_names: Option<&[String]>,
+    since: u128,
+    until: u128,
+    limit: usize,
+) -> Result<Vec<LogEvent>, String> {
+    let prefix = process_key(project_path, "");
+    let buffers = manager
+        .log_buffers
+        .lock()
+        .map_err(|_| "Log buffer poisoned".to_string())?;
+    let mut events: Vec<LogEvent> = buffers
+        .iter()
+        .filter(|(key, _)| {
+            key.strip_prefix(&prefix).is_some_and(|name| {
+                process_names.is_none_or(|names| names.iter().any(|n| n == name))
+            })
+        })
+        .flat_map(|(_, buffer)| buffer.iter())
+        .filter(|event| (since..=until).contains(&event.timestamp_ms))
+        .cloned()
+        .collect();
+    drop(buffers);
+    // Stable, so lines read in the same millisecond keep their order within a process.
+    events.sort_by_key(|event| event.timestamp_ms);
+    let skip = events.len().saturating_sub(limit);
+    events.drain(..skip);
+    Ok(events)
+}
+
+/// The process's buffered output as one newline-joined string for the UI to put on the
+/// clipboard, e.g. for a bug report. Lines come as the process wrote them unless `strip_ansi`.
+#[tauri::command(rename_all = "camelCase")]
+fn copy_logs_to_clipboard(
+    state: State<ProcessManager>,
+    project_path: String,
+    process_name: String,
+    limit: Option<usize>,
+    strip_ansi: bool,
+) -> Result<String, String> {
+    let events = get_logs(state, project_path, process_name, limit)?;
+    Ok(clipboard_text(&events, strip_ansi))
+}
+
+fn clipboard_text(events: &[LogEvent], strip: bool) -> String {
+    events
+        .iter()
+        .map(|event| {
+            let line = event.raw.as_deref().unwrap_or(&event.line);
+            if strip {
+                strip_ansi(line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Sends a signal such as `SIGHUP` or `USR1` to a running process's group, leaving the
+/// supervisor alone (a signal that ends the process is treated like any other exit).
+#[tauri::command(rename_all = "camelCase")]
+fn signal_process(
+    state: State<ProcessManager>,
+    project_path: String,
+    process_name: String,
+    signal: String,
+) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        let signal = parse_signal(&signal)?;
+        let pid = state
+            .processes
+            .lock()
+            .map_err(|_| "Process map poisoned".to_string())?
+            .get(&process_key(&project_path, &process_name))
+            .map(|entry| entry.pid)
+            .filter(|pid| *pid > 0)
+            .ok_or_else(|| "Process not running".to_string())?;
+        signal_process_group(pid, signal);
+        Ok(())
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (state, project_path, process_name, signal);
+        Err("Signals are not supported on this platform".to_string())
+    }
+}
+
+/// Asks a running process to reload without restarting: sends its `reload_signal` and/or runs
+/// its `reload_command`. The status reads "reloading" until both are done.
+#[tauri::command(rename_all = "camelCase")]
+fn reload_process(
+    app: AppHandle,
+    state: State<ProcessManager>,
+    settings: State<SettingsStore>,
+    project_path: String,
+    process_name: String,
+) -> Result<(), String> {
+    let process = resolve_process_config(Path::new(&project_path), &process_name)?;
+    if process.reload_signal.is_none() && process.reload_command.is_none() {
+        return Err(format!(
+            "No reload_signal or reload_command configured for {}",
+            process_name
+        ));
+    }
+
+    #[cfg(unix)]
+    let signal = process
+        .reload_signal
+        .as_deref()
+        .map(parse_signal)
+        .transpose()?;
+    #[cfg(not(unix))]
+    if process.reload_signal.is_some() {
+        return Err("reload_signal is only supported on Unix".to_string());
+    }
+
+    let plan = match &process.reload_command {
+        Some(command) => {
+            let reload = ProcessConfig {
+                command: command.clone(),
+                ..process.clone()
+            };
+            let shell = login_shell(&settings.get());
+            Some(plan_spawn(&shell, Path::new(&project_path), &reload)?)
+        }
+        None => None,
+    };
+
+    let manager = state.inner().clone();
+    let key = process_key(&project_path, &process_name);
+    let pid = {
+        let mut map = manager
+            .processes
+            .lock()
+            .map_err(|_| "Process map poisoned".to_string())?;
+        let Some(entry) = map.get_mut(&key) else {
+            return Err("Process not running".to_string());
+        };
+        entry.status = "reloading".to_string();
+        entry.pid
+    };
+    let sink = EventSink::App(app);
+    emit_status(&sink, &project_path, &process_name, "reloading", None);
+
+    #[cfg(unix)]
+    if let Some(signal) = signal {
+        signal_process_group(pid, signal);
+    }
+    #[cfg(not(unix))]
+    let _ = pid;
+
+    let finish = move |sink: &EventSink, project_path: &str, process_name: &str| {
+        let mut restored = false;
+        if let Ok(mut map) = manager.processes.lock() {
+            let entry = map.get_mut(&key);
+            if let Some(entry) = entry.filter(|entry| entry.status == "reloading") {
+                entry.status = "running".to_string();
+                restored = true;
+            }
+        }
+        if restored {
+            emit_status(sink, project_path, process_name, "running", None);
+        }
+    };
+
+    let Some(plan) = plan else {
+        finish(&sink, &project_path, &process_name);
+        return Ok(());
+    };
+
+    // Reload commands may take a while; don't hold up the caller.
+    thread::spawn(move || {
+        let log = |line| emit_log(&sink, &project_path, &process_name, line, "system", None);
+        match plan.command().stdin(Stdio::null()).output() {
+            Ok(output) => {
+                for stream in [&output.stdout, &output.stderr] {
+                    String::from_utf8_lossy(stream)
+                        .lines()
+                        .for_each(|line| log(line.to_string()));
+                }
+                if !output.status.success() {
+                    log(format!("[reload] command exited with {}", output.status));
+                }
+            }
+            Err(err) => log(format!("[reload] Failed to run reload_command: {}", err)),
+        }
+        finish(&sink, &project_path, &process_name);
+    });
+
+    Ok(())
+}
+
+/// Restarts a process in place: its group gets SIGTERM (SIGKILL after the stop grace period)
+/// and the supervisor relaunches it in the same slot, so the map entry isn't recreated.
+///
+/// Passing `command` or `autorestart` needs a fresh supervisor instead: the process is stopped,
+/// its entry is awaited until gone, and it's started again like `start_process`. A missing
+/// `command` falls back to the configured one and a missing `autorestart` to the current flag.
+#[tauri::command(rename_all = "camelCase", async)]
+fn restart_process(
+    app: AppHandle,
+    state: State<ProcessManager>,
+    settings: State<SettingsStore>,
+    project_path: String,
+    process_name: String,
+    command: Option<String>,
+    autorestart: Option<bool>,
+) -> Result<(), String> {
+    let manager = state.inner();
+    let key = process_key(&project_path, &process_name);
+    if command.is_none() && autorestart.is_none() {
+        return restart_managed_process(manager, &key, false);
+    }
+
+    let (current_autorestart, stop_timeout) = manager
+        .processes
+        .lock()
+        .map_err(|_| "Process map poisoned".to_string())?
+        .get(&key)
+        .map(|entry| {
+            (
+                entry.autorestart.load(Ordering::SeqCst),
+                entry.stop_duration(),
+            )
+        })
+        .ok_or_else(|| "Process not running".to_string())?;
+    let command = match command {
+        Some(command) => command,
+        None => resolve_process_config(Path::new(&project_path), &process_name)?.command,
+    };
+    validate_command(&process_name, &command)?;
+
+    stop_managed_process(manager, key.clone(), None, SHUTDOWN_POLL_INTERVAL)?;
+    wait_until_removed(manager, &key, stop_timeout + RESTART_STOP_MARGIN)
+        .map_err(|err| format!("{}: {}", process_name, err))?;
+    let sink = EventSink::App(app.clone());
+    emit_status(&sink, &project_path, &process_name, "restarting", None);
+
+    let settings = settings.get();
+    let mut process = process_to_start(&settings, &project_path, process_name, command)?;
+    process.autorestart = autorestart.unwrap_or(current_autorestart);
+    start_configured(app, manager, &settings, project_path, process)
+}
+
+/// How long past its stop timeout a restarted process's supervisor gets to clean up its entry.
+const RESTART_STOP_MARGIN: Duration = Duration::from_secs(2);
+
+/// Polls until the supervisor has removed the entry for `key`, so the slot can be reused.
+fn wait_until_removed(
+    manager: &ProcessManager,
+    key: &str,
+    timeout: Duration,
+) -> Result<(), String> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let present = manager
+            .processes
+            .lock()
+            .map_err(|_| "Process map poisoned".to_string())?
+            .contains_key(key);
+        if !present {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            return Err(format!("didn't stop within {}s", timeout.as_secs()));
+        }
+        thread::sleep(SHUTDOWN_POLL_INTERVAL);
+    }
+}
+
+/// Like `restart_process`, but a process waiting out its autorestart delay is retried at once
+/// instead of rejected.
+#[tauri::command(rename_all = "camelCase")]
+fn restart_now(
+    state: State<ProcessManager>,
+    project_path: String,
+    process_name: String,
+) -> Result<(), String> {
+    let key = process_key(&project_path, &process_name);
+    restart_managed_process(state.inner(), &key, true)
+}
+
+fn restart_managed_process(
+    manager: &ProcessManager,
+    key: &str,
+    skip_pending_wait: bool,
+) -> Result<(), String> {
+    let (pid, restart, container, steps) = {
+        let map = manager
+            .processes
+            .lock()
+            .map_err(|_| "Process map poisoned".to_string())?;
+        let Some(entry) = map.get(key) else {
+            return Err("Process not running".to_string());
+        };
+        let Some(restart) = entry.restart.clone() else {
+            return Err("Scheduled and attached processes can't be restarted".to_string());
+        };
+        #[cfg(windows)]
+        if entry.pid > 0 {
+            if let Some(job) = &entry.job {
+                restart.requested.store(true, Ordering::SeqCst);
+                job.terminate();
+                if let Some(container) = entry.container.clone() {
+                    let grace = entry.stop_duration();
+                    thread::spawn(move || container.stop(grace));
+                }
+                return Ok(());
+            }
+        }
+        (
+            entry.pid,
+            restart,
+            entry.container.clone(),
+            stop_steps(entry, None),
+        )
+    };
+
+    if pid == 0 {
+        if !skip_pending_wait {
+            return Err("Process is between runs".to_string());
+        }
+        restart.skip_wait.store(true, Ordering::SeqCst);
+        return Ok(());
+    }
+
+    restart.requested.store(true, Ordering::SeqCst);
+
+    thread::spawn(move || {
+        // Stop the container first and wait for it: signalling only ends the runtime CLI, and
+        // the relaunch reuses the container's name.
+        if let Some(container) = container {
+            container.stop(steps.iter().map(|step| step.wait).sum());
+        }
+        // Only escalate against this run's group; the relaunched child gets a new pgid.
+        #[cfg(unix)]
+        walk_stop_steps(&steps, SHUTDOWN_POLL_INTERVAL, || pid);
+        #[cfg(not(unix))]
+        let _ = (pid, steps);
+    });
+
+    Ok(())
+}
+
+/// Starts forwarding a process's output lines that match `include` (and not `exclude`), and are
+/// at `min_level` or more severe when given, on a dedicated `process-log-filtered:<id>` event,
+/// so chatty processes can be grepped without shipping every line to the UI. Returns the
+/// subscription id for `unsubscribe_filtered_logs`.
+#[tauri::command(rename_all = "camelCase")]
+fn subscribe_filtered_logs(
+    state: State<ProcessManager>,
+    project_path: String,
+    process_name: String,
+    include: Option<String>,
+    exclude: Option<String>,
+    min_level: Option<LogLevel>,
+) -> Result<u64, String> {
+    let compile = |pattern: Option<String>, field: &str| {
+        pattern
+            .filter(|pattern| !pattern.is_empty())
+            .map(|pattern| Regex::new(&pattern))
+            .transpose()
+            .map_err(|err| format!("Invalid {} pattern: {}", field, err))
+    };
+    let subscription = LogSubscription {
+        key: process_key(&project_path, &process_name),
+        include: compile(include, "include")?,
+        exclude: compile(exclude, "exclude")?,
+        min_level,
+    };
+
+    let id = state.next_subscription_id.fetch_add(1, Ordering::SeqCst) + 1;
+    state
+        .log_subscriptions
+        .lock()
+        .map_err(|_| "Log subscriptions poisoned".to_string())?
+        .insert(id, subscription);
+    Ok(id)
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn unsubscribe_filtered_logs(
+    state: State<ProcessManager>,
+    subscription_id: u64,
+) -> Result<(), String> {
+    let removed = state
+        .log_subscriptions
+        .lock()
+        .map_err(|_| "Log subscriptions poisoned".to_string())?
+        .remove(&subscription_id);
+    match removed {
+        Some(_) => Ok(()),
+        None => Err(format!("Unknown log subscription {}", subscription_id)),
+    }
+}
+
+/// Sets the stop flag and terminates the process group, escalating to SIGKILL as soon as it has
+/// outlived `grace`.
+fn stop_managed_process(
+    manager: &ProcessManager,
+    key: String,
+    grace: Option<Duration>,
+    poll: Duration,
+) -> Result<(), String> {
+    let (pid, stop_flag, container, steps) = {
+        let map = manager
+            .processes
+            .lock()
+            .map_err(|_| "Process map poisoned".to_string())?;
+        let Some(entry) = map.get(&key) else {
+            return Err("Process not running".to_string());
+        };
+        // Windows has no SIGTERM to give the tree a chance to exit, so it ends right away.
+        #[cfg(windows)]
+        if let Some(job) = &entry.job {
+            entry.stop_flag.store(true, Ordering::SeqCst);
+            job.terminate();
+        }
+        (
+            entry.pid,
+            entry.stop_flag.clone(),
+            entry.container.clone(),
+            stop_steps(entry, grace),
+        )
+    };
+
+    stop_flag.store(true, Ordering::SeqCst);
+    if let Some(container) = container {
+        let grace = steps.iter().map(|step| step.wait).sum();
+        thread::spawn(move || container.stop(grace));
+    }
+
+    #[cfg(unix)]
+    {
+        // Gracefully stop the whole process tree, escalating while it's still alive.
+        let manager = manager.clone();
+        thread::spawn(move || {
+            walk_stop_steps(&steps, poll, || {
+                let map = manager.processes.lock().ok();
+                map.and_then(|map| map.get(&key).map(|p| p.pid))
+                    .filter(|pid| *pid > 0)
+                    .unwrap_or(pid)
+            })
+        });
+    }
+
+    #[cfg(not(unix))]
+    let _ = (pid, steps, poll);
+
+    Ok(())
+}
+
+/// A resolved `stop_sequence` step.
+struct StopSignal {
+    /// `None` on platforms without signals.
+    signal: Option<i32>,
+    wait: Duration,
+}
+
+/// The steps to stop `entry` with: SIGTERM, then SIGKILL after `grace` (or the entry's
+/// `stop_timeout`), unless the process has a `stop_sequence` and no `grace` was asked for.
+fn stop_steps(entry: &ManagedProcess, grace: Option<Duration>) -> Vec<StopSignal> {
+    #[cfg(unix)]
+    let parse = |name: &str| parse_signal(name).ok();
+    #[cfg(not(unix))]
+    let parse = |_: &str| None;
+    if grace.is_none() && !entry.stop_sequence.is_empty() {
+        return entry
+            .stop_sequence
+            .iter()
+            .map(|step| StopSignal {
+                signal: parse(&step.signal),
+                wait: Duration::from_secs(step.wait_secs),
+            })
+            .collect();
+    }
+    vec![
+        StopSignal {
+            signal: parse("TERM"),
+            wait: grace.unwrap_or(entry.stop_timeout),
+        },
+        StopSignal {
+            signal: parse("KILL"),
+            wait: Duration::ZERO,
+        },
+    ]
+}
+
+/// Sends each step's signal to the group `current_pid` returns and waits for it to exit,
+/// stopping as soon as the group is gone.
+#[cfg(unix)]
+fn walk_stop_steps(steps: &[StopSignal], poll: Duration, current_pid: impl Fn() -> u32) {
+    for step in steps {
+        let pid = current_pid();
+        if !process_group_exists(pid) {
+            return;
+        }
+        if let Some(signal) = step.signal {
+            signal_process_group(pid, signal);
+        }
+        if wait_for_groups_exit(&[pid], step.wait, poll) {
+            return;
+        }
+    }
+}
+
+fn tagged_processes(project_path: &Path, tag: &str) -> Result<Vec<ProcessConfig>, String> {
+    let config = read_project_config(project_path)?;
+    let tagged: Vec<ProcessConfig> = config
+        .processes
+        .into_iter()
+        .filter(|process| process.tags.iter().any(|t| t == tag))
+        .collect();
+    if tagged.is_empty() {
+        return Err(format!("No processes tagged {}", tag));
+    }
+    Ok(tagged)
+}
+
+fn process_usage_for(
+    manager: &ProcessManager,
+    settings: &AppSettings,
+) -> Result<ProcessUsage, String> {
+    let map = manager
+        .processes
+        .lock()
+        .map_err(|_| "Process map poisoned".to_string())?;
+    let long_running = map.values().filter(|entry| !entry.one_shot).count();
+    let one_shot = map.len() - long_running;
+    let counted = if settings.limit_counts_one_shot {
+        map.len()
+    } else {
+        long_running
+    };
+
+    Ok(ProcessUsage {
+        long_running,
+        one_shot,
+        counted,
+        limit: settings.max_total_processes,
+    })
+}
+
+/// Refuses to start another process once `max_total_processes` is reached.
+fn check_process_limit(
+    manager: &ProcessManager,
+    settings: &AppSettings,
+    process: &ProcessConfig,
+) -> Result<(), String> {
+    if process.is_one_shot() && !settings.limit_counts_one_shot {
+        return Ok(());
+    }
+    let usage = process_usage_for(manager, settings)?;
+    match usage.limit {
+        Some(limit) if usage.counted >= limit => Err(PROCESS_LIMIT_REACHED.to_string()),
+        _ => Ok(()),
+    }
+}
+
+/// Error returned when `max_total_processes` is reached. It's a fixed string so the UI can
+/// recognise it and offer to stop something instead of showing a generic failure.
+const PROCESS_LIMIT_REACHED: &str = "Process limit reached; stop a process or raise the limit";
+
+#[tauri::command(rename_all = "camelCase")]
+fn process_usage(
+    state: State<ProcessManager>,
+    settings: State<SettingsStore>,
+) -> Result<ProcessUsage, String> {
+    process_usage_for(state.inner(), &settings.get())
+}
+
+/// Starts every process in the project carrying `tag`, using the commands from the config file.
+/// Like `start_all_processes`, each waits for its `depends_on` (tagged or not) in the background
+/// and failures go to its log. Returns the start order.
+#[tauri::command(rename_all = "camelCase")]
+fn start_tag(
+    app: AppHandle,
+    state: State<ProcessManager>,
+    settings: State<SettingsStore>,
+    project_path: String,
+    tag: String,
+) -> Result<Vec<String>, String> {
+    tagged_processes(Path::new(&project_path), &tag)?;
+    start_in_order(
+        app,
+        state.inner(),
+        settings.get(),
+        project_path,
+        |process| process.tags.contains(&tag),
+    )
+}
+
+/// Orders processes (as indices) so each comes after everything in its `depends_on`, keeping
+/// config order otherwise. Names that match no process are ignored here. A cycle is an error
+/// that spells it out, e.g. `dependency cycle: api -> db -> api`.
+fn dependency_order(processes: &[ProcessConfig]) -> Result<Vec<usize>, String> {
+    let by_name: HashMap<&str, usize> = processes
+        .iter()
+        .enumerate()
+        .map(|(index, process)| (process.name.as_str(), index))
+        .collect();
+    let dependencies: Vec<Vec<usize>> = processes
+        .iter()
+        .map(|process| {
+            process
+                .depends_on
+                .iter()
+                .filter_map(|name| by_name.get(name.as_str()).copied())
+                .collect()
+        })
+        .collect();
+
+    let mut placed = vec![false; processes.len()];
+    let mut order = Vec::new();
+    loop {
+        let ready: Vec<usize> = (0..processes.len())
+            .filter(|&index| !placed[index] && dependencies[index].iter().all(|&dep| placed[dep]))
+            .collect();
+        if ready.is_empty() {
+            break;
+        }
+        for index in ready {
+            placed[index] = true;
+            order.push(index);
+        }
+    }
+
+    let Some(start) = placed.iter().position(|placed| !placed) else {
+        return Ok(order);
+    };
+    // Everything left waits on something else that's left, so following those edges loops.
+    let mut path = vec![start];
+    loop {
+        let last = path[path.len() - 1];
+        let Some(next) = dependencies[last].iter().copied().find(|&dep| !placed[dep]) else {
+            break;
+        };
+        if let Some(position) = path.iter().position(|&index| index == next) {
+            let cycle: Vec<&str> = path[position..]
+                .iter()
+                .chain([&next])
+                .map(|&index| processes[index].name.as_str())
+                .collect();
+            return Err(format!("dependency cycle: {}", cycle.join(" -> ")));
+        }
+        path.push(next);
+    }
+    Err("dependency cycle".to_string())
+}
+
+/// Polls until the process reports "running". Fails if it stops being managed (it crashed
+/// without autorestart, was stopped, or was never started) or `timeout` passes.
+fn wait_until_running(
+    manager: &ProcessManager,
+    project_path: &str,
+    process_name: &str,
+    timeout: Duration,
+) -> Result<(), String> {
+    let key = process_key(project_path, process_name);
+    let deadline = Instant::now() + timeout;
+    loop {
+        let status = manager
+            .processes
+            .lock()
+            .map_err(|_| "Process map poisoned".to_string())?
+            .get(&key)
+            .map(|entry| entry.status.clone());
+        match status.as_deref() {
+            Some("running") => return Ok(()),
+            Some(_) if Instant::now() < deadline => thread::sleep(Duration::from_millis(200)),
+            Some(_) => return Err(format!("{} didn't become ready in time", process_name)),
+            None => return Err(format!("{} isn't running", process_name)),
+        }
+    }
+}
+
+/// Starts every enabled process in the project, dependencies first: a process is only started
+/// once everything in its `depends_on` reports "running". Unknown dependencies and cycles are
+/// refused up front; the rest happens in the background, and a process whose dependency never
+/// gets there is skipped with a message in its log. Returns the start order.
+#[tauri::command(rename_all = "camelCase")]
+fn start_all_processes(
+    app: AppHandle,
+    state: State<ProcessManager>,
+    settings: State<SettingsStore>,
+    project_path: String,
+) -> Result<Vec<String>, String> {
+    start_in_order(app, state.inner(), settings.get(), project_path, |_| true)
+}
+
+/// Starts the project's processes that `wanted` picks, dependencies first, in the background.
+/// Disabled processes are always left out. A dependency doesn't have to be picked itself, but
+/// has to be running by then. Failures go to the process's log. Returns the start order.
+fn start_in_order(
+    app: AppHandle,
+    manager: &ProcessManager,
+    settings: AppSettings,
+    project_path: String,
+    wanted: impl Fn(&ProcessConfig) -> bool,
+) -> Result<Vec<String>, String> {
+    let config = read_project_config(Path::new(&project_path))?;
+    let order = start_order(&config.processes)?;
+    let manager = manager.clone();
+    let sink = EventSink::App(app);
+    let shell = login_shell(&settings);
+
+    let mut processes = config.processes;
+    let timeouts = dependency_timeouts(&processes);
+    let ordered: Vec<ProcessConfig> = order
+        .iter()
+        .map(|&index| std::mem::take(&mut processes[index]))
+        .filter(|process| {
+            wanted(process)
+                && !settings
+                    .disabled_processes
+                    .contains(&process_key(&project_path, &process.name))
+        })
+        .collect();
+    let names = ordered.iter().map(|process| process.name.clone()).collect();
+
+    thread::spawn(move || {
+        for process in ordered {
+            let name = process.name.clone();
+            let result = start_after_dependencies(
+                &sink,
+                &manager,
+                &settings,
+                &shell,
+                &project_path,
+                process,
+                &timeouts,
+            );
+            if let Err(err) = result {
+                emit_log(&sink, &project_path, &name, err, "system", None);
+            }
+        }
+    });
+
+    Ok(names)
+}
+
+/// `dependency_order`, but a `depends_on` naming a process that isn't in the config is an error.
+fn start_order(processes: &[ProcessConfig]) -> Result<Vec<usize>, String> {
+    for process in processes {
+        if let Some(missing) = process
+            .depends_on
+            .iter()
+            .find(|name| !processes.iter().any(|p| &p.name == *name))
+        {
+            return Err(format!(
+                "{} depends on {}, which isn't in the config",
+                process.name, missing
+            ));
+        }
+    }
+    dependency_order(processes)
+}
+
+/// How long a dependent waits for each process to report "running": its ready timeout plus
+/// its startup grace.
+fn dependency_timeouts(processes: &[ProcessConfig]) -> HashMap<String, Duration> {
+    processes
+        .iter()
+        .map(|process| {
+            let ready = process
+                .ready_timeout_secs
+                .map_or(DEFAULT_READY_TIMEOUT, Duration::from_secs);
+            let grace = Duration::from_secs(process.startup_grace_secs.unwrap_or(0));
+            (process.name.clone(), ready + grace)
+        })
+        .collect()
+}
+
+/// Waits for everything `process` depends on, then starts it unless it's already running.
+fn start_after_dependencies(
+    sink: &EventSink,
+    manager: &ProcessManager,
+    settings: &AppSettings,
+    shell: &str,
+    project_path: &str,
+    process: ProcessConfig,
+    timeouts: &HashMap<String, Duration>,
+) -> Result<(), String> {
+    process
+        .depends_on
+        .iter()
+        .try_for_each(|dependency| {
+            let timeout = timeouts[dependency];
+            wait_until_running(manager, project_path, dependency, timeout)
+        })
+        .map_err(|err| format!("Not started: {}", err))?;
+    let key = process_key(project_path, &process.name);
+    let already_running = manager
+        .processes
+        .lock()
+        .map(|map| map.contains_key(&key))
+        .unwrap_or(false);
+    if already_running {
+        return Ok(());
+    }
+    check_process_limit(manager, settings, &process)?;
+    spawn_supervisor(
+        sink.clone(),
+        manager.clone(),
+        shell.to_string(),
+        project_path.to_string(),
+        process,
+    )
+}
+
+/// Stops the project's running processes, waits for them to exit, then starts them again from
+/// the current config in dependency order, along with any `autostart` process that wasn't
+/// running. With nothing running it just starts the autostart processes. A failure to stop
+/// or start one process is reported in its outcome and doesn't hold up the others.
+#[tauri::command(rename_all = "camelCase", async)]
+fn restart_all_processes(
+    app: AppHandle,
+    state: State<ProcessManager>,
+    settings: State<SettingsStore>,
+    project_path: String,
+) -> Result<Vec<ProcessOutcome>, String> {
+    let config = read_project_config(Path::new(&project_path))?;
+    let order = start_order(&config.processes)?;
+    let settings = settings.get();
+    let manager = state.inner();
+    let sink = EventSink::App(app);
+    let shell = login_shell(&settings);
+
+    let running: Vec<(String, Duration)> = manager
+        .processes
+        .lock()
+        .map_err(|_| "Process map poisoned".to_string())?
+        .values()
+        .filter(|entry| entry.project_path == project_path)
+        .map(|entry| (entry.process_name.get(), entry.stop_duration()))
+        .collect();
+    let mut stop_errors: HashMap<String, String> = running
+        .iter()
+        .filter_map(|(name, _)| {
+            let key = process_key(&project_path, name);
+            stop_managed_process(manager, key, None, SHUTDOWN_POLL_INTERVAL)
+                .err()
+                .map(|err| (name.clone(), err))
+        })
+        .collect();
+    for (name, stop_timeout) in &running {
+        if stop_errors.contains_key(name) {
+            continue;
+        }
+        let key = process_key(&project_path, name);
+        if let Err(err) = wait_until_removed(manager, &key, *stop_timeout + RESTART_STOP_MARGIN) {
+            stop_errors.insert(name.clone(), err);
+        }
+    }
+
+    let timeouts = dependency_timeouts(&config.processes);
+    let mut processes = config.processes;
+    let mut outcomes = Vec::new();
+    for index in order {
+        let process = std::mem::take(&mut processes[index]);
+        let name = process.name.clone();
+        if let Some(err) = stop_errors.remove(&name) {
+            outcomes.push(ProcessOutcome::new(&name, Err(err)));
+            continue;
+        }
+        let was_running = running
+            .iter()
+            .any(|(running_name, _)| *running_name == name);
+        let disabled = settings
+            .disabled_processes
+            .contains(&process_key(&project_path, &name));
+        if disabled || !(was_running || process.autostart) {
+            continue;
+        }
+        let result = start_after_dependencies(
+            &sink,
+            manager,
+            &settings,
+            &shell,
+            &project_path,
+            process,
+            &timeouts,
+        );
+        outcomes.push(ProcessOutcome::new(&name, result));
+    }
+    // Whatever is left was running without being in the config any more.
+    outcomes.extend(
+        stop_errors
+            .into_iter()
+            .map(|(name, err)| ProcessOutcome::new(&name, Err(err))),
+    );
+    Ok(outcomes)
+}
+
+/// Stops every running process in the project carrying `tag`.
+#[tauri::command(rename_all = "camelCase")]
+fn stop_tag(
+    state: State<ProcessManager>,
+    project_path: String,
+    tag: String,
+) -> Result<Vec<ProcessOutcome>, String> {
+    let processes = tagged_processes(Path::new(&project_path), &tag)?;
+
+    Ok(processes
+        .iter()
+        .map(|process| {
+            let key = process_key(&project_path, &process.name);
+            let result = stop_managed_process(state.inner(), key, None, SHUTDOWN_POLL_INTERVAL);
+            ProcessOutcome::new(&process.name, result)
+        })
+        .collect())
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn write_to_process(
+    app: AppHandle,
+    state: State<ProcessManager>,
+    project_path: String,
+    process_name: String,
+    input: String,
+) -> Result<(), String> {
+    let key = process_key(&project_path, &process_name);
+    let (stdin, mode, recording) = {
+        let map = state
+            .processes
+            .lock()
+            .map_err(|_| "Process map poisoned".to_string())?;
+        let Some(entry) = map.get(&key) else {
+            return Err("Process not running".to_string());
+        };
+        (
+            entry.stdin.clone(),
+            entry.stdin_mode,
+            entry.recording.clone(),
+        )
+    };
+    if let Some(recording) = recording {
+        if let Err(err) = recording.write("i", input.as_bytes()) {
+            let sink = EventSink::App(app);
+            recording.report_failure(&sink, &project_path, &process_name, err);
+        }
+    }
+
+    let mut handle = stdin
+        .lock()
+        .map_err(|_| "Process stdin poisoned".to_string())?;
+    if handle.pipe.is_none() {
+        return Err("Process stdin not available".to_string());
+    }
+
+    match handle.write(mode, &input) {
+        Ok(()) => Ok(()),
+        Err(err) if is_closed_pipe(&err) => {
+            // The reading end is gone; drop the handle so later writes fail fast.
+            *handle = StdinPipe::default();
+            Err("Process stdin closed".to_string())
+        }
+        Err(err) => Err(format!("Failed to write to stdin: {}", err)),
+    }
+}
+
+/// Closes a `record: true` process's recording early; the process keeps running. Returns the
+/// recording's path.
+#[tauri::command(rename_all = "camelCase")]
+fn stop_recording(
+    state: State<ProcessManager>,
+    project_path: String,
+    process_name: String,
+) -> Result<String, String> {
+    let key = process_key(&project_path, &process_name);
+    let recording = {
+        let map = state
+            .processes
+            .lock()
+            .map_err(|_| "Process map poisoned".to_string())?;
+        let Some(entry) = map.get(&key) else {
+            return Err("Process not running".to_string());
+        };
+        entry.recording.clone()
+    };
+    match recording {
+        Some(recording) if recording.stop() => Ok(recording.path.to_string_lossy().to_string()),
+        _ => Err("Process is not being recorded".to_string()),
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RecordingInfo {
+    path: String,
+    file_name: String,
+    size_bytes: u64,
+    /// Milliseconds since the Unix epoch.
+    modified_ms: Option<u64>,
+}
+
+/// The project's `.cast` files, oldest first. Empty when nothing was recorded yet.
+#[tauri::command(rename_all = "camelCase")]
+fn list_recordings(project_path: String) -> Result<Vec<RecordingInfo>, String> {
+    let dir = recordings_dir(Path::new(&project_path));
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(format!("{} ({})", err, dir.display())),
+    };
+
+    let mut recordings: Vec<RecordingInfo> = entries
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "cast"))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            let modified_ms = metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|elapsed| elapsed.as_millis() as u64);
+            Some(RecordingInfo {
+                path: entry.path().to_string_lossy().to_string(),
+                file_name: entry.file_name().to_string_lossy().to_string(),
+                size_bytes: metadata.len(),
+                modified_ms,
+            })
+        })
+        .collect();
+    recordings.sort_by_key(|recording| recording.modified_ms);
+    Ok(recordings)
+}
+
+fn is_closed_pipe(err: &std::io::Error) -> bool {
+    matches!(
+        err.kind(),
+        std::io::ErrorKind::BrokenPipe | std::io::ErrorKind::WouldBlock
+    )
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn run_action(
+    settings: State<SettingsStore>,
+    project_path: String,
+    command: String,
+) -> Result<(), String> {
+    if command.trim().is_empty() {
+        return Err("Missing command".to_string());
+    }
+
+    let mut cmd = Command::new(login_shell(&settings.get()));
+    cmd.arg("-lc")
+        .arg(&command)
+        .current_dir(&project_path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        unsafe {
+            cmd.pre_exec(|| {
+                // Detach from the app process so actions can open external apps freely.
+                libc::setsid();
+                Ok(())
+            });
+        }
+    }
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|err| format!("Failed to run action: {}", err))?;
+
+    // Reap in background to avoid zombies, but don't track as a managed process.
+    thread::spawn(move || {
+        let _ = child.wait();
+    });
+
+    Ok(())
+}
+
+const GITHUB_REPO_API: &str = "https://api.github.com/repos/porterabbott/myterm";
+
+const GITHUB_API_ATTEMPTS: u32 = 3;
+
+enum FetchError {
+    /// No usable response (timeout, connection reset, 5xx); worth another try.
+    Transient(String),
+    /// A definitive answer such as 404 or a 403 rate limit; retrying won't help.
+    Fatal(String),
+}
+
+/// A GitHub token from `GH_TOKEN` or `GITHUB_TOKEN`, used to lift the anonymous rate limit.
+fn github_token() -> Option<String> {
+    ["GH_TOKEN", "GITHUB_TOKEN"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .map(|token| token.trim().to_string())
+        .find(|token| !token.is_empty())
+}
+
+/// One GitHub API request: a GET, or a POST of the JSON in `body` when given.
+fn github_api_attempt(url: &str, body: Option<&Path>) -> Result<Vec<u8>, FetchError> {
+    let token = github_token();
+    let mut cmd = Command::new("curl");
+    cmd.args(["-sL", "--max-time", "15", "-w", "\n%{http_code}"])
+        .args(["-H", "Accept: application/vnd.github+json"]);
+    if let Some(body) = body {
+        cmd.args(["-X", "POST", "-H", "Content-Type: application/json"])
+            .arg("--data-binary")
+            .arg(format!("@{}", body.display()));
+    }
+    if token.is_some() {
+        // Read the auth header from stdin so the token doesn't show up in `ps`.
+        cmd.args(["-H", "@-"]).stdin(Stdio::piped());
+    }
+    let mut child = cmd
+        .arg(url)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| FetchError::Fatal(format!("Failed to fetch updates: {}", err)))?;
+    if let (Some(token), Some(mut stdin)) = (token, child.stdin.take()) {
+        let _ = writeln!(stdin, "Authorization: Bearer {}", token);
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|err| FetchError::Fatal(format!("Failed to fetch updates: {}", err)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(FetchError::Transient(format!(
+            "gh api failed: {}",
+            stderr.trim()
+        )));
+    }
+
+    // `-w` appends the HTTP status on its own line after the body.
+    let mut body = output.stdout;
+    let split = body.iter().rposition(|byte| *byte == b'\n').unwrap_or(0);
+    let code = String::from_utf8_lossy(&body[split..])
+        .trim()
+        .parse::<u16>()
+        .unwrap_or(0);
+    body.truncate(split);
+
+    match code {
+        200..=299 => Ok(body),
+        500..=599 | 0 => Err(FetchError::Transient(format!(
+            "GitHub API returned HTTP {}",
+            code
+        ))),
+        _ => Err(FetchError::Fatal(format!(
+            "GitHub API returned HTTP {}",
+            code
+        ))),
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GithubApiStatus {
+    token_detected: bool,
+    limit: u64,
+    remaining: u64,
+    /// Unix time (seconds) when `remaining` resets.
+    reset_at: u64,
+}
+
+#[derive(Deserialize)]
+struct GithubRateLimit {
+    rate: GithubRate,
+}
+
+#[derive(Deserialize)]
+struct GithubRate {
+    limit: u64,
+    remaining: u64,
+    reset: u64,
+}
+
+/// Diagnoses update-check failures: whether a token is in use and how much of the rate limit
+/// is left. Querying `/rate_limit` doesn't count against the limit.
+#[tauri::command(rename_all = "camelCase")]
+fn github_api_status() -> Result<GithubApiStatus, String> {
+    let token_detected = github_token().is_some();
+    let body = github_api_get("https://api.github.com/rate_limit").map_err(|err| {
+        if token_detected && err.ends_with("HTTP 401") {
+            format!("{} (check GH_TOKEN/GITHUB_TOKEN)", err)
+        } else {
+            err
+        }
+    })?;
+    let limits: GithubRateLimit = serde_json::from_slice(&body).map_err(|err| err.to_string())?;
+    Ok(GithubApiStatus {
+        token_detected,
+        limit: limits.rate.limit,
+        remaining: limits.rate.remaining,
+        reset_at: limits.rate.reset,
+    })
+}
+
+/// GETs a GitHub API URL, retrying transient failures with an increasing delay.
+fn github_api_get(url: &str) -> Result<Vec<u8>, String> {
+    let mut last_error = String::new();
+    for attempt in 0..GITHUB_API_ATTEMPTS {
+        if attempt > 0 {
+            thread::sleep(Duration::from_millis(500 << attempt));
+        }
+        match github_api_attempt(url, None) {
+            Ok(body) => return Ok(body),
+            Err(FetchError::Fatal(err)) => return Err(err),
+            Err(FetchError::Transient(err)) => last_error = err,
+        }
+    }
+    Err(last_error)
+}
+
+/// Most recent lines per process `create_log_gist` includes.
+const GIST_LOG_LINES: usize = 500;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LogGist {
+    /// The secret gist's page; `None` when no GitHub token is set.
+    url: Option<String>,
+    /// Everything that went into the gist, as one text, for copying when there's no gist.
+    content: String,
+}
+
+#[derive(Serialize)]
+struct GistRequest<'a> {
+    description: String,
+    public: bool,
+    files: BTreeMap<&'a str, GistFile<'a>>,
+}
+
+#[derive(Serialize)]
+struct GistFile<'a> {
+    content: &'a str,
+}
+
+#[derive(Deserialize)]
+struct GithubGist {
+    html_url: String,
+}
+
+/// The buffered output lines of each of the project's processes, by process name.
+fn buffered_project_logs(
+    manager: &ProcessManager,
+    project_path: &str,
+) -> Result<BTreeMap<String, Vec<String>>, String> {
+    let prefix = process_key(project_path, "");
+    let buffers = manager
+        .log_buffers
+        .lock()
+        .map_err(|_| "Log buffer poisoned".to_string())?;
+    Ok(buffers
+        .iter()
+        .filter_map(|(key, buffer)| {
+            let process_name = key.strip_prefix(&prefix)?;
+            let lines = buffer.iter().map(|event| event.line.clone()).collect();
+            Some((process_name.to_string(), lines))
+        })
+        .collect())
+}
+
+/// Bundles the project's config and the given processes' logs for sharing a repro. With
+/// `GH_TOKEN`/`GITHUB_TOKEN` set (the token needs the gist scope) they're uploaded as a secret
+/// gist. `logs` maps process names to their lines; when it's empty, every process of the
+/// project with buffered output (see `get_logs`) is included.
+#[tauri::command(rename_all = "camelCase")]
+fn create_log_gist(
+    state: State<ProcessManager>,
+    project_path: String,
+    logs: BTreeMap<String, Vec<String>>,
+) -> Result<LogGist, String> {
+    let logs = if logs.is_empty() {
+        buffered_project_logs(state.inner(), &project_path)?
+    } else {
+        logs
+    };
+    let path = Path::new(&project_path);
+    let mut files: BTreeMap<String, String> = BTreeMap::new();
+    if let Some(config_path) = find_existing_config_path(path) {
+        let contents = fs::read_to_string(&config_path)
+            .map_err(|err| format!("{} ({})", err, config_path.display()))?;
+        let name = config_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "myterm.yml".to_string());
+        files.insert(name, contents);
+    }
+
+    let last_exits = state
+        .last_exits
+        .lock()
+        .map_err(|_| "Exit history poisoned".to_string())?
+        .clone();
+    for (process_name, lines) in &logs {
+        let mut text = String::new();
+        if let Some(exit) = last_exits.get(&process_key(&project_path, process_name)) {
+            let code = exit
+                .code
+                .map_or("signal".to_string(), |code| code.to_string());
+            text.push_str(&format!("# last exit: {}\n", code));
+        }
+        let skip = lines.len().saturating_sub(GIST_LOG_LINES);
+        for line in &lines[skip..] {
+            text.push_str(line);
+            text.push('\n');
+        }
+        files.insert(format!("{}.log", service_slug(process_name)), text);
+    }
+    if files.is_empty() {
+        return Err("Nothing to share: no config and no logs".to_string());
+    }
+
+    let content = files
+        .iter()
+        .map(|(name, text)| format!("==> {} <==\n{}", name, text))
+        .collect::<Vec<_>>()
+        .join("\n");
+    if github_token().is_none() {
+        return Ok(LogGist { url: None, content });
+    }
+
+    let project_name = get_project_name(project_path.clone());
+    let request = GistRequest {
+        description: format!("myterm logs: {}", project_name),
+        public: false,
+        files: files
+            .iter()
+            .map(|(name, content)| (name.as_str(), GistFile { content }))
+            .collect(),
+    };
+    let request = serde_json::to_vec(&request).map_err(|err| err.to_string())?;
+    let dir = create_temp_dir("myterm-gist")?;
+    let body_path = dir.join("gist.json");
+    fs::write(&body_path, request).map_err(|err| err.to_string())?;
+    let response = github_api_attempt("https://api.github.com/gists", Some(&body_path));
+    let _ = fs::remove_dir_all(&dir);
+    let body = response.map_err(|err| match err {
+        FetchError::Transient(err) | FetchError::Fatal(err) => err,
+    })?;
+    let gist: GithubGist = serde_json::from_slice(&body).map_err(|err| err.to_string())?;
+    Ok(LogGist {
+        url: Some(gist.html_url),
+        content,
+    })
+}
+
+/// A registered project as shown in the recent-projects list.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProjectEntry {
+    path: String,
+    name: String,
+    /// Whether the folder still has a config; it may have been moved or deleted since.
+    has_config: bool,
+}
+
+/// Remembers a project folder so `list_projects` offers it next launch. Re-adding a project
+/// moves it to the top.
+#[tauri::command(rename_all = "camelCase")]
+fn add_project(settings: State<SettingsStore>, path: String) -> Result<(), String> {
+    if find_existing_config_path(Path::new(&path)).is_none() {
+        return Err(format!("No config found in {}", path));
+    }
+    let mut updated = settings.get();
+    updated.projects.retain(|project| *project != path);
+    updated.projects.insert(0, path);
+    settings.save(updated)
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn remove_project(settings: State<SettingsStore>, path: String) -> Result<(), String> {
+    let mut updated = settings.get();
+    let before = updated.projects.len();
+    updated.projects.retain(|project| *project != path);
+    if updated.projects.len() == before {
+        return Ok(());
+    }
+    settings.save(updated)
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn list_projects(settings: State<SettingsStore>) -> Vec<ProjectEntry> {
+    settings
+        .get()
+        .projects
+        .into_iter()
+        .map(|path| {
+            let project_path = Path::new(&path);
+            ProjectEntry {
+                name: detect_project_name(project_path),
+                has_config: find_existing_config_path(project_path).is_some(),
+                path,
+            }
+        })
+        .collect()
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn get_settings(settings: State<SettingsStore>) -> AppSettings {
+    settings.get()
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn update_settings(
+    settings: State<SettingsStore>,
+    new_settings: AppSettings,
+) -> Result<AppSettings, String> {
+    if let Some(shell) = &new_settings.default_shell {
+        validate_shell(shell)?;
+    }
+    settings.save(new_settings.clone())?;
+    Ok(new_settings)
+}
+
+/// The updater swaps `.app` bundles on macOS and AppImages on Linux, so it only works when
+/// running from one of those (not e.g. `tauri dev` or a distro package).
+fn self_update_support() -> Result<(), String> {
+    if !cfg!(any(target_os = "macos", target_os = "linux")) {
+        return Err("Self-update is only available on macOS and Linux".to_string());
+    }
+    installed_app_path().map(|_| ())
+}
+
+/// Lets the UI hide update controls where `install_update`/`restart_app` can't work.
+#[tauri::command(rename_all = "camelCase")]
+fn supports_self_update() -> SelfUpdateSupport {
+    match self_update_support() {
+        Ok(()) => SelfUpdateSupport {
+            supported: true,
+            reason: None,
+        },
+        Err(reason) => SelfUpdateSupport {
+            supported: false,
+            reason: Some(reason),
+        },
+    }
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn check_for_update(app: AppHandle, force: Option<bool>) -> Result<UpdateInfo, String> {
+    let current_version = app.package_info().version.to_string();
+
+    let release = latest_release(force.unwrap_or(false))?;
+    let latest_tag = release.tag_name.clone();
+    let latest_version = latest_tag.trim_start_matches('v');
+    let available = is_newer_version(latest_version, &current_version);
+
+    let (download_url, checksum_url) = if available {
+        let asset = release.update_asset().ok_or_else(|| {
+            format!(
+                "Update available, but {} asset not found",
+                UPDATE_ASSET_NAME
+            )
+        })?;
+        (
+            asset.browser_download_url.clone(),
+            release.checksum_url(asset),
+        )
+    } else {
+        (String::new(), None)
+    };
+
+    Ok(UpdateInfo {
+        available,
+        version: latest_tag,
+        download_url,
+        checksum_url,
+    })
+}
+
+/// How long a fetched latest release is reused, to stay clear of GitHub's rate limit.
+const LATEST_RELEASE_TTL: Duration = Duration::from_secs(10 * 60);
+
+static LATEST_RELEASE: Mutex<Option<(Instant, GithubRelease)>> = Mutex::new(None);
+
+/// The latest release, from the cache unless it's stale or `force` is set (e.g. right after
+/// publishing a release). A failed fetch leaves the cache as it was.
+fn latest_release(force: bool) -> Result<GithubRelease, String> {
+    if !force {
+        if let Ok(cached) = LATEST_RELEASE.lock() {
+            if let Some((fetched_at, release)) = cached.as_ref() {
+                if fetched_at.elapsed() < LATEST_RELEASE_TTL {
+                    return Ok(release.clone());
+                }
+            }
+        }
+    }
+
+    let body = github_api_get(&format!("{}/releases/latest", GITHUB_REPO_API))?;
+    let release: GithubRelease = serde_json::from_slice(&body).map_err(|err| err.to_string())?;
+    if let Ok(mut cached) = LATEST_RELEASE.lock() {
+        *cached = Some((Instant::now(), release.clone()));
+    }
+    Ok(release)
+}
+
+/// Lists the most recent releases (newest first) that ship an update for this platform (a
+/// `MyTerm.zip`, or an AppImage on Linux), so the UI can offer
+/// a version picker. Any returned `download_url` can be passed to `install_update`, including
+/// older ones for a manual downgrade.
+#[tauri::command(rename_all = "camelCase")]
+fn list_releases(limit: Option<u32>) -> Result<Vec<ReleaseInfo>, String> {
+    let per_page = limit.unwrap_or(10).clamp(1, 100);
+    let url = format!("{}/releases?per_page={}", GITHUB_REPO_API, per_page);
+    let body = github_api_get(&url)?;
+    let releases: Vec<GithubRelease> =
+        serde_json::from_slice(&body).map_err(|err| err.to_string())?;
+
+    Ok(releases
+        .into_iter()
+        .filter_map(|release| {
+            let asset = release.update_asset()?;
+            Some(ReleaseInfo {
+                download_url: asset.browser_download_url.clone(),
+                checksum_url: release.checksum_url(asset),
+                version: release.tag_name.clone(),
+                published_at: release.published_at.clone(),
+                prerelease: release.prerelease,
+            })
+        })
+        .collect())
+}
+
+/// Free space the updater wants on the temp and install volumes, for the download, the
+/// extracted bundle and the copy swapped into place.
+const UPDATE_MIN_FREE_BYTES: u64 = 500 * 1024 * 1024;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PreconditionCheck {
+    name: String,
+    passed: bool,
+    detail: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdatePreconditions {
+    /// True when every check passed, i.e. `install_update` will go ahead.
+    ready: bool,
+    checks: Vec<PreconditionCheck>,
+}
+
+#[cfg(unix)]
+fn free_disk_space(path: &Path) -> Option<u64> {
+    use std::os::unix::ffi::OsStrExt;
+    let path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    // The field widths differ between platforms, so the conversions aren't always no-ops.
+    #[allow(clippy::useless_conversion)]
+    let free = u64::from(stat.f_bavail).saturating_mul(u64::from(stat.f_frsize));
+    Some(free)
+}
+
+fn update_preconditions(manager: &ProcessManager) -> UpdatePreconditions {
+    let mut checks = Vec::new();
+    let mut check = |name: &str, passed: bool, detail: String| {
+        checks.push(PreconditionCheck {
+            name: name.to_string(),
+            passed,
+            detail,
+        });
+    };
+
+    let bundle = self_update_support().and_then(|()| installed_app_path());
+    match &bundle {
+        Ok(path) => check("supported", true, path.display().to_string()),
+        Err(err) => check("supported", false, err.clone()),
+    }
+
+    let install_dir = bundle
+        .ok()
+        .and_then(|path| path.parent().map(Path::to_path_buf));
+    let volumes = [("temp", Some(env::temp_dir())), ("install", install_dir)];
+    for (label, dir) in volumes {
+        let Some(dir) = dir else {
+            continue;
+        };
+        let name = format!("disk_space_{}", label);
+        #[cfg(unix)]
+        let free = free_disk_space(&dir);
+        #[cfg(not(unix))]
+        let free: Option<u64> = None;
+        match free {
+            Some(free) => check(
+                &name,
+                free >= UPDATE_MIN_FREE_BYTES,
+                format!(
+                    "{} MiB free in {} (need {} MiB)",
+                    free / (1024 * 1024),
+                    dir.display(),
+                    UPDATE_MIN_FREE_BYTES / (1024 * 1024)
+                ),
+            ),
+            None => check(
+                &name,
+                false,
+                format!("Could not read free space in {}", dir.display()),
+            ),
+        }
+    }
+
+    // One-shot runs would be cut off by the relaunch; attached processes aren't ours to finish.
+    let busy: Vec<String> = manager
+        .processes
+        .lock()
+        .map(|map| {
+            map.values()
+                .filter(|entry| entry.plan.is_some() && entry.one_shot)
+                .filter(|entry| entry.status == "starting" || entry.status == "running")
+                .map(|entry| entry.process_name.get())
+                .collect()
+        })
+        .unwrap_or_default();
+    if busy.is_empty() {
+        check(
+            "one_shot_idle",
+            true,
+            "No one-shot tasks running".to_string(),
+        );
+    } else {
+        check(
+            "one_shot_idle",
+            false,
+            format!("Still running: {}", busy.join(", ")),
+        );
+    }
+
+    let ready = checks.iter().all(|check| check.passed);
+    UpdatePreconditions { ready, checks }
+}
+
+/// What `install_update` checks before touching anything, so the UI can show why an update
+/// can't be installed yet (or ask for confirmation when it can).
+#[tauri::command(rename_all = "camelCase")]
+fn check_update_preconditions(state: State<ProcessManager>) -> UpdatePreconditions {
+    update_preconditions(state.inner())
+}
+
+/// Fetches a `sha256sum`-style checksum file (`<hex digest>  <file name>`, or just the digest)
+/// and returns the digest in lowercase.
+fn fetch_expected_sha256(checksum_url: &str) -> Result<String, String> {
+    let output = Command::new("curl")
+        .args(["-fsL"])
+        .arg(checksum_url)
+        .output()
+        .map_err(|err| format!("Failed to run curl: {}", err))?;
+    if !output.status.success() {
+        return Err("Checksum download failed".to_string());
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    let digest = text.split_whitespace().next().unwrap_or_default();
+    if digest.len() != 64 || !digest.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return Err("Checksum file doesn't contain a SHA-256 digest".to_string());
+    }
+    Ok(digest.to_ascii_lowercase())
+}
+
+fn verify_sha256(path: &Path, expected: &str) -> Result<(), String> {
+    use sha2::{Digest, Sha256};
+    let mut file = fs::File::open(path).map_err(|err| format!("{} ({})", err, path.display()))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).map_err(|err| err.to_string())?;
+    let actual: String = hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    if actual != expected {
+        return Err(format!(
+            "Downloaded update failed checksum verification (expected {}, got {})",
+            expected, actual
+        ));
+    }
+    Ok(())
+}
+
+/// `update-progress` payload. `total_bytes` is unset when the server doesn't say.
+#[derive(Serialize, Clone)]
+struct UpdateProgressEvent {
+    downloaded_bytes: u64,
+    total_bytes: Option<u64>,
+}
+
+const UPDATE_PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+/// The size of what `url` (after redirects) serves, from a HEAD request.
+fn content_length(url: &str) -> Option<u64> {
+    let output = Command::new("curl")
+        .args(["-fsIL"])
+        .arg(url)
+        .output()
+        .ok()?;
+    // One header block per redirect hop; only the last block describes the file.
+    let mut length = None;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if line.starts_with("HTTP/") {
+            length = None;
+        } else if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().ok();
+            }
+        }
+    }
+    length.filter(|length| *length > 0)
+}
+
+/// Downloads `url` to `dest` with curl, emitting `update-progress` as the file grows. An
+/// interrupted or short download is an error and `dest` is removed, so a partial file never
+/// reaches the unzip/install step.
+fn download_update(app: &AppHandle, url: &str, dest: &Path) -> Result<(), String> {
+    let total_bytes = content_length(url);
+    // `-f` makes HTTP errors fail instead of saving an error page as the download.
+    let mut child = Command::new("curl")
+        .args(["-fsL", "-o"])
+        .arg(dest)
+        .arg(url)
+        .stdin(Stdio::null())
+        .spawn()
+        .map_err(|err| format!("Failed to run curl: {}", err))?;
+
+    let (status, downloaded_bytes) = loop {
+        let exited = child.try_wait().map_err(|err| err.to_string())?;
+        let downloaded_bytes = fs::metadata(dest).map(|meta| meta.len()).unwrap_or(0);
+        let _ = app.emit(
+            "update-progress",
+            UpdateProgressEvent {
+                downloaded_bytes,
+                total_bytes,
+            },
+        );
+        if let Some(status) = exited {
+            break (status, downloaded_bytes);
+        }
+        thread::sleep(UPDATE_PROGRESS_INTERVAL);
+    };
+
+    let failure = if !status.success() {
+        Some(match status.code() {
+            Some(18) | Some(56) => "Update download was interrupted".to_string(),
+            Some(code) => format!("Update download failed (curl exit code {})", code),
+            None => "Update download failed".to_string(),
+        })
+    } else {
+        total_bytes
+            .filter(|total| *total != downloaded_bytes)
+            .map(|total| {
+                format!(
+                    "Update download was incomplete ({} of {} bytes)",
+                    downloaded_bytes, total
+                )
+            })
+    };
+    match failure {
+        Some(err) => {
+            let _ = fs::remove_file(dest);
+            Err(err)
+        }
+        None => Ok(()),
+    }
+}
+
+/// Installs the update at `download_url`. With a `checksum_url` (from `check_for_update`) the
+/// download must match it; without one it's installed unverified, with a warning on stderr.
+/// Runs off the main thread (`async`) so the window stays responsive and can show
+/// `update-progress`.
+#[tauri::command(rename_all = "camelCase", async)]
+fn install_update(
+    app: AppHandle,
+    state: State<ProcessManager>,
+    download_url: String,
+    checksum_url: Option<String>,
+) -> Result<(), String> {
+    if download_url.trim().is_empty() {
+        return Err("Missing download URL".to_string());
+    }
+    let preconditions = update_preconditions(state.inner());
+    if !preconditions.ready {
+        let failed: Vec<String> = preconditions
+            .checks
+            .into_iter()
+            .filter(|check| !check.passed)
+            .map(|check| check.detail)
+            .collect();
+        return Err(format!("Can't install update: {}", failed.join("; ")));
+    }
+    let expected_sha256 = match checksum_url.as_deref().filter(|url| !url.trim().is_empty()) {
+        Some(url) => Some(fetch_expected_sha256(url)?),
+        None => {
+            eprintln!("myterm: no checksum published for this update; installing it unverified");
+            None
+        }
+    };
+    if cfg!(target_os = "linux") {
+        return install_appimage_update(&app, &download_url, expected_sha256.as_deref());
+    }
+
+    let app_bundle = find_app_bundle_path()?;
+    let _app_parent = app_bundle
+        .parent()
+        .ok_or_else(|| "Could not determine app bundle parent".to_string())?;
+
+    let temp_dir = create_temp_dir("myterm-update")?;
+    let extracted_app =
+        match fetch_app_bundle(&app, &download_url, expected_sha256.as_deref(), &temp_dir) {
+            Ok(path) => path,
+            Err(err) => {
+                let _ = fs::remove_dir_all(&temp_dir);
+                return Err(err);
+            }
+        };
+
+    let _ = Command::new("xattr")
+        .arg("-cr")
+        .arg(&extracted_app)
+        .status();
+
+    // Stage the new bundle next to the installed one so the swap below is two renames on the
+    // same volume. The installed bundle isn't touched until staging has fully succeeded.
+    let staged_bundle = app_bundle.with_extension("app.new");
+    let staged = stage_app_bundle(&extracted_app, &staged_bundle);
+    let _ = fs::remove_dir_all(&temp_dir);
+    if let Err(err) = staged {
+        let _ = fs::remove_dir_all(&staged_bundle);
+        return Err(err);
+    }
+
+    // The old bundle is kept as `.app.old` (the running binary lives there) until the restart
+    // helper has launched the new one.
+    let backup_bundle = app_bundle.with_extension("app.old");
+    let _ = fs::remove_dir_all(&backup_bundle);
+    if let Err(err) = fs::rename(&app_bundle, &backup_bundle) {
+        let _ = fs::remove_dir_all(&staged_bundle);
+        return Err(format!("Failed to move old app bundle: {}", err));
+    }
+    if let Err(err) = fs::rename(&staged_bundle, &app_bundle) {
+        let restored = fs::rename(&backup_bundle, &app_bundle);
+        let _ = fs::remove_dir_all(&staged_bundle);
+        return Err(match restored {
+            Ok(()) => format!("Failed to move new app bundle into place: {}", err),
+            Err(restore_err) => format!(
+                "Failed to move new app bundle into place: {}; the previous version is at {} ({})",
+                err,
+                backup_bundle.display(),
+                restore_err
+            ),
+        });
+    }
+
+    Ok(())
+}
+
+/// Copies the extracted bundle to `staged` and checks the copy is complete.
+fn stage_app_bundle(extracted_app: &Path, staged: &Path) -> Result<(), String> {
+    if staged.exists() {
+        fs::remove_dir_all(staged).map_err(|err| format!("{} ({})", err, staged.display()))?;
+    }
+    let copy_status = Command::new("cp")
+        .args(["-R"])
+        .arg(extracted_app)
+        .arg(staged)
+        .status()
+        .map_err(|err| err.to_string())?;
+    if !copy_status.success() {
+        return Err("Failed to copy new app bundle".to_string());
+    }
+    validate_app_bundle(staged)?;
+    let _ = Command::new("xattr").arg("-cr").arg(staged).status();
+    Ok(())
+}
+
+/// ELF magic followed by the AppImage type 2 marker (`AI\x02` at offset 8).
+fn is_appimage(path: &Path) -> bool {
+    let mut header = [0u8; 11];
+    fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .is_ok()
+        && header.starts_with(b"\x7fELF")
+        && &header[8..] == b"AI\x02"
+}
+
+/// Replaces the running AppImage with the one at `download_url`. The download goes to a temp
+/// file next to it and is renamed over the old path, so a failed download leaves the old one
+/// alone; the running copy keeps working from the replaced inode until the relaunch.
+fn install_appimage_update(
+    app: &AppHandle,
+    download_url: &str,
+    expected_sha256: Option<&str>,
+) -> Result<(), String> {
+    let appimage = find_appimage_path()?;
+    let file_name = appimage
+        .file_name()
+        .ok_or_else(|| "Could not determine AppImage file name".to_string())?;
+    let temp_path = appimage.with_file_name(format!(".{}.update", file_name.to_string_lossy()));
+    let discard = |err: String| {
+        let _ = fs::remove_file(&temp_path);
+        err
+    };
+
+    download_update(app, download_url, &temp_path).map_err(discard)?;
+    if let Some(expected) = expected_sha256 {
+        verify_sha256(&temp_path, expected).map_err(discard)?;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&temp_path, fs::Permissions::from_mode(0o755))
+            .map_err(|err| discard(format!("{} ({})", err, temp_path.display())))?;
+    }
+    if !is_appimage(&temp_path) {
+        return Err(discard("Downloaded update is not an AppImage".to_string()));
+    }
+
+    fs::rename(&temp_path, &appimage)
+        .map_err(|err| discard(format!("{} ({})", err, appimage.display())))
+}
+
+/// Waits for this process to exit, then reopens `app_bundle` (an AppImage on Linux) and deletes
+/// `backup_bundle` if one is given.
+fn spawn_restart_helper(app_bundle: &Path, backup_bundle: Option<&Path>) -> Result<(), String> {
+    let temp_dir = create_temp_dir("myterm-update")?;
+    let script_path = temp_dir.join("restart.sh");
+    let script = r#"#!/bin/sh
+TARGET_PID="$MYTERM_PID"
+APP_BUNDLE="$MYTERM_APP"
+BACKUP_BUNDLE="$MYTERM_BACKUP"
+
+i=0
+while [ $i -lt 15 ]; do
+  if ! kill -0 "$TARGET_PID" 2>/dev/null; then
+    break
+  fi
+  sleep 0.1
+  i=$((i+1))
+done
+
+sleep 0.5
+if [ "$(uname)" = "Darwin" ]; then
+  /usr/bin/open -n "$APP_BUNDLE" >/dev/null 2>&1
+else
+  "$APP_BUNDLE" >/dev/null 2>&1 &
+fi
+LAUNCHED=$?
+if [ -n "$BACKUP_BUNDLE" ] && [ -d "$BACKUP_BUNDLE" ]; then
+  if [ "$LAUNCHED" -eq 0 ]; then
+    sleep 1
+    /bin/rm -rf "$BACKUP_BUNDLE" >/dev/null 2>&1
+  else
+    # The new bundle didn't launch; put the previous one back and start that instead.
+    /bin/rm -rf "$APP_BUNDLE" >/dev/null 2>&1
+    /bin/mv "$BACKUP_BUNDLE" "$APP_BUNDLE" && /usr/bin/open -n "$APP_BUNDLE" >/dev/null 2>&1
+  fi
+fi
+"#;
+
+    fs::write(&script_path, script).map_err(|err| err.to_string())?;
+
+    let mut cmd = Command::new("/bin/sh");
+    cmd.arg(&script_path)
+        .env("MYTERM_PID", format!("{}", std::process::id()))
+        .env("MYTERM_APP", app_bundle)
+        .env("MYTERM_BACKUP", backup_bundle.unwrap_or(Path::new("")))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        unsafe {
+            cmd.pre_exec(|| {
+                libc::setsid();
+                Ok(())
+            });
+        }
+    }
+
+    cmd.spawn().map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+#[tauri::command(rename_all = "camelCase")]
+fn restart_app(app: AppHandle, state: State<RestartState>) -> Result<(), String> {
+    // An AppImage is replaced in place, so there's no backup to clean up.
+    let (app_bundle, backup_bundle) = if cfg!(target_os = "linux") {
+        (find_appimage_path()?, None)
+    } else {
+        let running_bundle = find_app_bundle_path()?;
+        let app_bundle = resolve_primary_app_bundle_path(&running_bundle);
+        let backup_bundle = app_bundle.with_extension("app.old");
+        (app_bundle, Some(backup_bundle))
+    };
+
+    if !app_bundle.exists() {
+        return Err(format!(
+            "Updated app bundle not found at {}",
+            app_bundle.display()
+        ));
+    }
+
+    // Stop all managed processes before exiting (so we don't orphan dev servers)
+    let manager = app.state::<ProcessManager>();
+    let sink = EventSink::App(app.clone());
+    shutdown_all_processes(manager.inner(), &sink, Duration::from_millis(500));
+
+    spawn_restart_helper(&app_bundle, backup_bundle.as_deref())?;
+    state.mark_update_restart();
+    // Hard exit — bypass Tauri window cleanup to avoid hangs
+    // The setsid helper script survives this and relaunches the app
+    std::process::exit(0);
+}
+
+/// Relaunches the running bundle as-is, e.g. after changing a global setting. Unlike
+/// `restart_app` there's no bundle swap, and managed processes get the same shutdown as quitting.
+#[tauri::command(rename_all = "camelCase")]
+fn relaunch_app(app: AppHandle) -> Result<(), String> {
+    let running_bundle = installed_app_path()?;
+
+    let manager = app.state::<ProcessManager>();
+    let sink = EventSink::App(app.clone());
+    shutdown_all_processes(manager.inner(), &sink, Duration::from_millis(800));
+
+    spawn_restart_helper(&running_bundle, None)?;
+    // Exiting directly skips the exit handler, which would stop the (already stopped) processes.
+    std::process::exit(0);
+}
+
+/// A project passed on the command line (`myterm ~/code/app` or `myterm --project <path>`).
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct LaunchProject {
+    path: String,
+    /// False when the directory has no config yet, so the UI can offer to create one.
+    has_config: bool,
+}
+
+/// Handed out once via `take_launch_project`.
+#[derive(Default)]
+struct LaunchProjectState(Mutex<Option<LaunchProject>>);
+
+/// The project directory given in `args` (without the program name): the value of
+/// `--project`, else the first argument that isn't a flag. A config file path stands for its
+/// directory. Returns an error for a path that doesn't exist.
+fn launch_project_arg(args: &[String]) -> Result<Option<LaunchProject>, String> {
+    let path = match args.iter().position(|arg| arg == "--project") {
+        Some(idx) => args.get(idx + 1),
+        // macOS may pass `-psn_...` and similar; only bare arguments count.
+        None => args.iter().find(|arg| !arg.starts_with('-')),
+    };
+    let Some(path) = path else {
+        return Ok(None);
+    };
+    let path = std::path::absolute(path).map_err(|err| format!("{} ({})", err, path))?;
+    let dir = if path.is_file() {
+        path.parent().map(Path::to_path_buf).unwrap_or(path)
+    } else if path.is_dir() {
+        path
+    } else {
+        return Err(format!("No such directory: {}", path.display()));
+    };
+    Ok(Some(LaunchProject {
+        has_config: find_existing_config_path(&dir).is_some(),
+        path: dir.to_string_lossy().to_string(),
+    }))
+}
+
+/// The project given on the command line, if any. Returns it only to the first caller, so a
+/// reloaded window doesn't reopen it. Pulled by the UI on mount rather than pushed as an
+/// event, which could fire before the UI listens.
+#[tauri::command(rename_all = "camelCase")]
+fn take_launch_project(state: State<LaunchProjectState>) -> Option<LaunchProject> {
+    state.0.lock().ok().and_then(|mut project| project.take())
+}
+
+/// Problem with the configured shell found at startup, handed out once via
+/// `take_shell_warning`.
+#[derive(Default)]
+struct ShellWarningState(Mutex<Option<String>>);
+
+/// Why processes may fail to start because of the shell, if anything. Pulled by the UI on
+/// mount like `take_launch_project`, since `setup` runs before the UI can listen for events.
+#[tauri::command(rename_all = "camelCase")]
+fn take_shell_warning(state: State<ShellWarningState>) -> Option<String> {
+    state.0.lock().ok().and_then(|mut warning| warning.take())
+}
+
+/// Must match `identifier` in `tauri.conf.json`; Tauri names the app config dir after it.
+const APP_IDENTIFIER: &str = "com.porter.myterm";
+
+fn settings_path(config_dir: PathBuf) -> PathBuf {
+    config_dir.join("settings.json")
+}
+
+static HEADLESS_INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(unix)]
+extern "C" fn handle_headless_signal(_signal: libc::c_int) {
+    HEADLESS_INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+/// Runs the project's autostart processes without the desktop UI, printing their output to
+/// stdout. Returns once interrupted (SIGINT/SIGTERM) or when nothing is left running.
+///
+/// `config_path` may point at the project directory or at its config file.
+pub fn run_headless(config_path: &Path) -> Result<(), String> {
+    let project_path = if config_path.is_dir() {
+        config_path
+    } else {
+        config_path
+            .parent()
+            .ok_or_else(|| format!("Invalid config path {}", config_path.display()))?
+    };
+    let config = read_project_config(project_path)?;
+    let project_path = project_path.to_string_lossy().to_string();
+    let manager = ProcessManager::default();
+    // Same settings file as the desktop app, so its `default_shell` applies here too.
+    let settings =
+        SettingsStore::load(dirs::config_dir().map(|dir| settings_path(dir.join(APP_IDENTIFIER))));
+    let shell = login_shell(&settings.get());
+
+    #[cfg(unix)]
+    unsafe {
+        let handler = handle_headless_signal as extern "C" fn(libc::c_int);
+        libc::signal(libc::SIGINT, handler as libc::sighandler_t);
+        libc::signal(libc::SIGTERM, handler as libc::sighandler_t);
+    }
+
+    for process in config.processes.into_iter().filter(|p| p.autostart) {
+        let name = process.name.clone();
+        let sink = EventSink::Stdout;
+        let shell = shell.clone();
+        let project_path = project_path.clone();
+        if let Err(err) = spawn_supervisor(sink, manager.clone(), shell, project_path, process) {
+            eprintln!("[{}] failed to start: {}", name, err);
+        }
+    }
+
+    loop {
+        if HEADLESS_INTERRUPTED.load(Ordering::SeqCst) {
+            break;
+        }
+        let idle = manager
+            .processes
+            .lock()
+            .map(|map| map.is_empty())
+            .unwrap_or(true);
+        if idle {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+
+    shutdown_all_processes(&manager, &EventSink::Stdout, Duration::from_millis(800));
+
+    Ok(())
+}
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let launch_project = launch_project_arg(&args).unwrap_or_else(|err| {
+        eprintln!("myterm: {}", err);
+        None
+    });
+
+    tauri::Builder::default()
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_dialog::init())
+        .manage(ProcessManager::default())
         .manage(RestartState::default())
+        .manage(LaunchProjectState(Mutex::new(launch_project)))
+        .setup(|app| {
+            let settings_path = app.path().app_config_dir().ok().map(settings_path);
+            let settings = SettingsStore::load(settings_path);
+
+            // Validate once up front instead of failing every spawn with a confusing error.
+            let shell = login_shell(&settings.get());
+            let warning = validate_shell(&shell).err();
+            app.manage(ShellWarningState(Mutex::new(warning)));
+
+            app.manage(settings);
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             load_project_config,
+            get_project_name,
+            get_config_candidates,
             init_project_config,
             read_project_config_file,
             write_project_config_file,
+            set_autostart,
+            disable_process,
+            enable_process,
+            stop_recording,
+            list_recordings,
+            lint_config,
+            test_readiness_probe,
+            config_drift,
+            force_clear_process,
+            create_log_gist,
+            check_update_preconditions,
+            export_service_files,
             start_process,
+            run_once,
+            attach_pid,
+            kill_port_owner,
+            sync_state,
+            list_processes,
+            get_process_status,
+            set_autorestart,
+            prune_stopped,
+            debug_dump_state,
+            process_usage,
+            resolve_spawn_command,
+            get_resolved_process_config,
             stop_process,
+            rename_process,
+            reload_process,
+            signal_process,
+            get_logs,
+            copy_logs_to_clipboard,
+            get_merged_logs,
+            get_log_history,
+            search_logs,
+            restart_process,
+            restart_all_processes,
+            restart_now,
+            subscribe_filtered_logs,
+            unsubscribe_filtered_logs,
+            start_tag,
+            start_all_processes,
+            stop_tag,
             write_to_process,
             run_action,
+            get_settings,
+            add_project,
+            remove_project,
+            list_projects,
+            update_settings,
+            supports_self_update,
+            github_api_status,
             check_for_update,
+            list_releases,
             install_update,
-            restart_app
+            restart_app,
+            relaunch_app,
+            take_launch_project,
+            take_shell_warning
         ])
         .build(tauri::generate_context!())
         .expect("error while running tauri application")
@@ -1034,14 +7813,1109 @@ pub fn run() {
                 api.prevent_exit();
 
                 let manager = app_handle.state::<ProcessManager>();
-                let pgids = stop_all_processes(manager.inner());
-
-                #[cfg(unix)]
-                {
-                    wait_then_force_kill(pgids, Duration::from_millis(800), Duration::from_millis(800));
-                }
+                let sink = EventSink::App(app_handle.clone());
+                shutdown_all_processes(manager.inner(), &sink, Duration::from_millis(800));
 
                 app_handle.exit(0);
             }
         });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "PORT" => Some("8080".to_string()),
+            "EMPTY" => Some(String::new()),
+            "DANGER" => Some("a; rm -rf b".to_string()),
+            "QUOTED" => Some("it's \"q\" $HOME".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn expands_and_quotes_known_variables() {
+        assert_eq!(
+            expand_env_vars("node server.js --port ${PORT:-3000}", lookup),
+            "node server.js --port '8080'"
+        );
+        assert_eq!(
+            expand_env_vars("echo $PORT/${PORT}", lookup),
+            "echo '8080'/'8080'"
+        );
+        assert_eq!(
+            expand_env_vars("serve ${MISSING:-3000} ${EMPTY:-d} ${EMPTY}", lookup),
+            "serve '3000' 'd' ''"
+        );
+    }
+
+    #[test]
+    fn substituted_values_are_not_shell_code() {
+        assert_eq!(
+            expand_env_vars("echo $DANGER", lookup),
+            "echo 'a; rm -rf b'"
+        );
+        assert_eq!(
+            expand_env_vars("echo $QUOTED", lookup),
+            "echo 'it'\\''s \"q\" $HOME'"
+        );
+        assert_eq!(
+            expand_env_vars("echo \"x $DANGER $QUOTED\"", lookup),
+            "echo \"x a; rm -rf b it's \\\"q\\\" \\$HOME\""
+        );
+    }
+
+    #[test]
+    fn leaves_single_quotes_and_escapes_alone() {
+        assert_eq!(
+            expand_env_vars("awk '{print $NF}' $PORT", lookup),
+            "awk '{print $NF}' '8080'"
+        );
+        assert_eq!(
+            expand_env_vars("sh -c 'echo $PORT'", lookup),
+            "sh -c 'echo $PORT'"
+        );
+        assert_eq!(
+            expand_env_vars("echo \\$PORT \"\\$PORT\"", lookup),
+            "echo \\$PORT \"\\$PORT\""
+        );
+        assert_eq!(
+            expand_env_vars("echo \"it's $PORT\"", lookup),
+            "echo \"it's 8080\""
+        );
+    }
+
+    #[test]
+    fn leaves_unresolved_names_for_the_shell() {
+        assert_eq!(
+            expand_env_vars("source $NVM_DIR/nvm.sh && ${NVM_DIR}/x", lookup),
+            "source $NVM_DIR/nvm.sh && ${NVM_DIR}/x"
+        );
+        assert_eq!(
+            expand_env_vars("echo $1 $? ${PORT%0} ${1} $ ${PORT", lookup),
+            "echo $1 $? ${PORT%0} ${1} $ ${PORT"
+        );
+        assert_eq!(expand_env_vars("echo $$PORT", lookup), "echo $PORT");
+    }
+
+    fn process_from_yaml(yaml: &str) -> ProcessConfig {
+        serde_yaml::from_str(yaml).expect("valid process config")
+    }
+
+    #[test]
+    fn lint_reports_bad_stop_sequences() {
+        let processes = vec![
+            process_from_yaml(
+                "name: soft\ncommand: sleep 1\nstop_sequence:\n  - signal: INT\n    wait_secs: 2\n  - signal: TERM\n",
+            ),
+            process_from_yaml(
+                "name: typo\ncommand: sleep 1\nstop_sequence:\n  - signal: SIGSTOPP\n  - signal: KILL\n",
+            ),
+            process_from_yaml(
+                "name: ok\ncommand: sleep 1\nstop_sequence:\n  - signal: sigint\n    wait_secs: 2\n  - signal: SIGKILL\n",
+            ),
+        ];
+        let messages: Vec<(Option<String>, String)> = lint_processes(Path::new("."), &processes)
+            .into_iter()
+            .map(|diagnostic| (diagnostic.process_name, diagnostic.message))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                (
+                    Some("soft".to_string()),
+                    "Process soft stop_sequence must end with KILL".to_string()
+                ),
+                (
+                    Some("typo".to_string()),
+                    "Process typo stop_sequence: Unsupported signal: SIGSTOPP".to_string()
+                ),
+            ]
+        );
+    }
+
+    /// Runs `script` in its own process group, reaped in the background so the group
+    /// disappears as soon as it exits.
+    #[cfg(unix)]
+    fn spawn_group(script: &str) -> u32 {
+        use std::os::unix::process::CommandExt;
+        let mut child = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(script)
+            .process_group(0)
+            .spawn()
+            .expect("spawn sh");
+        let pid = child.id();
+        thread::spawn(move || child.wait());
+        pid
+    }
+
+    #[cfg(unix)]
+    fn step(signal: &str, wait: Duration) -> StopSignal {
+        StopSignal {
+            signal: parse_signal(signal).ok(),
+            wait,
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stop_ladder_escalates_past_trapped_signals() {
+        let pid = spawn_group("trap '' INT TERM; sleep 30");
+        // Let the shell install its traps before signalling it.
+        thread::sleep(Duration::from_millis(200));
+        let steps = [
+            step("INT", Duration::from_millis(300)),
+            step("TERM", Duration::from_millis(300)),
+            step("KILL", Duration::ZERO),
+        ];
+        let started = Instant::now();
+        walk_stop_steps(&steps, Duration::from_millis(20), || pid);
+        assert!(started.elapsed() >= Duration::from_millis(600));
+        assert!(wait_for_groups_exit(
+            &[pid],
+            Duration::from_secs(5),
+            Duration::from_millis(20)
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stop_ladder_stops_once_the_group_is_gone() {
+        let pid = spawn_group("exec sleep 30");
+        thread::sleep(Duration::from_millis(200));
+        let steps = [
+            step("INT", Duration::from_secs(10)),
+            step("KILL", Duration::ZERO),
+        ];
+        let started = Instant::now();
+        walk_stop_steps(&steps, Duration::from_millis(20), || pid);
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(!process_group_exists(pid));
+    }
+
+    #[test]
+    fn zip_test_rejects_truncated_archives() {
+        let dir = create_temp_dir("myterm-zip-test").unwrap();
+        fs::write(dir.join("payload.txt"), "x".repeat(64 * 1024)).unwrap();
+        let status = Command::new("zip")
+            .arg("-q")
+            .arg("update.zip")
+            .arg("payload.txt")
+            .current_dir(&dir)
+            .status()
+            .expect("run zip");
+        assert!(status.success());
+        let zip_path = dir.join("update.zip");
+        assert_eq!(test_zip(&zip_path), Ok(()));
+
+        let bytes = fs::read(&zip_path).unwrap();
+        fs::write(&zip_path, &bytes[..bytes.len() / 2]).unwrap();
+        assert_eq!(
+            test_zip(&zip_path),
+            Err("Downloaded update is corrupt or incomplete".to_string())
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn wait_for_groups_exit_sees_exited_groups() {
+        let pid = spawn_group("exit 0");
+        assert!(wait_for_groups_exit(
+            &[pid],
+            Duration::from_secs(5),
+            Duration::from_millis(20)
+        ));
+        assert!(!process_group_exists(pid));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn wait_for_groups_exit_times_out_on_trapped_groups() {
+        let pid = spawn_group("trap '' TERM; sleep 30");
+        thread::sleep(Duration::from_millis(200));
+        signal_process_group(pid, libc::SIGTERM);
+        let started = Instant::now();
+        assert!(!wait_for_groups_exit(
+            &[pid],
+            Duration::from_millis(300),
+            Duration::from_millis(20)
+        ));
+        assert!(started.elapsed() >= Duration::from_millis(300));
+        signal_process_group(pid, libc::SIGKILL);
+        assert!(wait_for_groups_exit(
+            &[pid],
+            Duration::from_secs(5),
+            Duration::from_millis(20)
+        ));
+    }
+
+    #[cfg(unix)]
+    fn track(manager: &ProcessManager, name: &str, pid: u32) -> (String, Arc<AtomicBool>) {
+        let key = process_key("/tmp/project", name);
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        manager.processes.lock().unwrap().insert(
+            key.clone(),
+            ManagedProcess {
+                pid,
+                project_path: "/tmp/project".to_string(),
+                process_name: SharedName::new(name.to_string()),
+                status: "running".to_string(),
+                autorestart: Arc::new(AtomicBool::new(false)),
+                one_shot: false,
+                stop_flag: stop_flag.clone(),
+                restart: None,
+                stdin_mode: StdinMode::Raw,
+                stdin: Arc::default(),
+                recording: None,
+                container: None,
+                plan: None,
+                started_at: Some(Instant::now()),
+                restart_count: 0,
+                stop_timeout: STOP_GRACE_PERIOD,
+                stop_sequence: Vec::new(),
+            },
+        );
+        (key, stop_flag)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stop_managed_process_stops_an_exiting_group() {
+        let manager = ProcessManager::default();
+        let pid = spawn_group("exit 0");
+        let (key, stop_flag) = track(&manager, "quick", pid);
+        let poll = Duration::from_millis(20);
+        assert_eq!(stop_managed_process(&manager, key, None, poll), Ok(()));
+        assert!(stop_flag.load(Ordering::SeqCst));
+        assert!(wait_for_groups_exit(&[pid], Duration::from_secs(5), poll));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stop_managed_process_kills_a_group_ignoring_term() {
+        let manager = ProcessManager::default();
+        let pid = spawn_group("trap '' TERM; sleep 30");
+        thread::sleep(Duration::from_millis(200));
+        let (key, stop_flag) = track(&manager, "stubborn", pid);
+        let grace = Duration::from_millis(300);
+        let poll = Duration::from_millis(20);
+        let started = Instant::now();
+        assert_eq!(
+            stop_managed_process(&manager, key, Some(grace), poll),
+            Ok(())
+        );
+        assert!(stop_flag.load(Ordering::SeqCst));
+        assert!(wait_for_groups_exit(&[pid], Duration::from_secs(5), poll));
+        assert!(started.elapsed() >= grace);
+    }
+
+    #[test]
+    fn stop_managed_process_rejects_unknown_keys() {
+        let manager = ProcessManager::default();
+        let key = process_key("/tmp/project", "missing");
+        assert_eq!(
+            stop_managed_process(&manager, key, None, SHUTDOWN_POLL_INTERVAL),
+            Err("Process not running".to_string())
+        );
+    }
+
+    fn plain_log_source(manager: ProcessManager, process_name: SharedName) -> LogSource {
+        LogSource {
+            sink: EventSink::Stdout,
+            manager,
+            project_path: "/tmp/project".to_string(),
+            process_name,
+            detect_links: false,
+            level_colors: None,
+            restart_trigger: None,
+            recording: None,
+            log_file: None,
+            encoding: OutputEncoding::default(),
+            strip_ansi: false,
+            run_id: None,
+        }
+    }
+
+    fn idle_supervisor(stop_flag: Arc<AtomicBool>) -> Supervisor {
+        let manager = ProcessManager::default();
+        let process_name = SharedName::new("web".to_string());
+        Supervisor {
+            sink: EventSink::Stdout,
+            manager: manager.clone(),
+            project_path: "/tmp/project".to_string(),
+            process_name: process_name.clone(),
+            plan: SpawnPlan {
+                shell: "sh".to_string(),
+                args: Vec::new(),
+                cwd: PathBuf::from("."),
+                env: BTreeMap::new(),
+                umask: None,
+                rlimits: ResourceLimits::default(),
+                container: None,
+            },
+            ready_check: None,
+            grace_until: None,
+            log_source: plain_log_source(manager, process_name),
+            stop_flag,
+            run_id: Arc::default(),
+            restart_delay: RESTART_DELAY,
+            max_restarts: None,
+        }
+    }
+
+    #[test]
+    fn wait_before_restart_waits_out_the_delay() {
+        let supervisor = idle_supervisor(Arc::default());
+        let started = Instant::now();
+        assert!(
+            supervisor.wait_before_restart(Duration::from_millis(300), &RestartControl::default())
+        );
+        assert!(started.elapsed() >= Duration::from_millis(300));
+    }
+
+    #[test]
+    fn wait_before_restart_ends_early_when_stopped() {
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let supervisor = idle_supervisor(stop_flag.clone());
+        let stopper = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            stop_flag.store(true, Ordering::SeqCst);
+        });
+        let started = Instant::now();
+        assert!(
+            !supervisor.wait_before_restart(Duration::from_secs(10), &RestartControl::default())
+        );
+        assert!(started.elapsed() < Duration::from_secs(5));
+        stopper.join().unwrap();
+    }
+
+    #[test]
+    fn wait_before_restart_can_be_skipped() {
+        let supervisor = idle_supervisor(Arc::default());
+        let restart = RestartControl::default();
+        restart.skip_wait.store(true, Ordering::SeqCst);
+        let started = Instant::now();
+        assert!(supervisor.wait_before_restart(Duration::from_secs(10), &restart));
+        assert!(started.elapsed() < Duration::from_secs(5));
+        // The request is used up, so the next wait runs in full.
+        assert!(!restart.skip_wait.load(Ordering::SeqCst));
+    }
+
+    const CRLF_CONFIG: &str =
+        "\u{feff}name: app\r\nprocesses:\r\n  - name: web\r\n    command: npm start\r\n    autostart: true\r\n";
+
+    #[test]
+    fn normalize_config_text_drops_bom_and_crlf() {
+        assert_eq!(
+            normalize_config_text(CRLF_CONFIG),
+            "name: app\nprocesses:\n  - name: web\n    command: npm start\n    autostart: true\n"
+        );
+        assert_eq!(normalize_config_text("a: 1\n"), "a: 1\n");
+    }
+
+    #[test]
+    fn write_config_text_keeps_line_endings() {
+        let dir = create_temp_dir("myterm-config-test").unwrap();
+        let crlf = dir.join("crlf.yml");
+        fs::write(&crlf, "a: 1\r\n").unwrap();
+        write_config_text(&crlf, "a: 2\nb: 3\n".to_string()).unwrap();
+        assert_eq!(fs::read_to_string(&crlf).unwrap(), "a: 2\r\nb: 3\r\n");
+
+        let lf = dir.join("lf.yml");
+        fs::write(&lf, "a: 1\n").unwrap();
+        write_config_text(&lf, "a: 2\nb: 3\n".to_string()).unwrap();
+        assert_eq!(fs::read_to_string(&lf).unwrap(), "a: 2\nb: 3\n");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn set_autostart_round_trips_bom_and_crlf() {
+        let dir = create_temp_dir("myterm-autostart-test").unwrap();
+        fs::write(dir.join("myterm.yml"), CRLF_CONFIG).unwrap();
+        let project = dir.to_string_lossy().to_string();
+
+        let config = set_autostart(project.clone(), "web".to_string(), false).unwrap();
+        assert!(!config.processes[0].autostart);
+        assert_eq!(
+            fs::read_to_string(dir.join("myterm.yml")).unwrap(),
+            CRLF_CONFIG.replace("autostart: true", "autostart: false")
+        );
+        let reread = read_project_config(&dir).unwrap();
+        assert!(!reread.processes[0].autostart);
+
+        set_autostart(project, "web".to_string(), true).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.join("myterm.yml")).unwrap(),
+            CRLF_CONFIG
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn configs_with_empty_commands_are_rejected() {
+        let dir = create_temp_dir("myterm-empty-command-test").unwrap();
+        fs::write(
+            dir.join("myterm.yml"),
+            "name: app\nprocesses:\n  - name: web\n    command: \"  \"\n",
+        )
+        .unwrap();
+        let err = read_project_config(&dir).unwrap_err();
+        assert!(err.starts_with("Process web has no command ("), "{}", err);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(validate_command("web", "npm start"), Ok(()));
+        assert_eq!(
+            validate_command("web", ""),
+            Err("Process web has no command".to_string())
+        );
+    }
+
+    /// A workspace with `packages/{web,api,lib}` (`lib` has no runnable script) and `tools/cli`.
+    fn sample_workspace(prefix: &str) -> PathBuf {
+        let dir = create_temp_dir(prefix).unwrap();
+        let packages = [
+            (
+                "packages/web",
+                r#"{"name": "@app/web", "scripts": {"dev": "vite", "start": "x"}}"#,
+            ),
+            (
+                "packages/api",
+                r#"{"name": "api", "scripts": {"start": "node ."}}"#,
+            ),
+            (
+                "packages/lib",
+                r#"{"name": "lib", "scripts": {"build": "tsc"}}"#,
+            ),
+            ("tools/cli", r#"{"scripts": {"dev": "tsx watch"}}"#),
+        ];
+        for (path, package_json) in packages {
+            fs::create_dir_all(dir.join(path)).unwrap();
+            fs::write(dir.join(path).join("package.json"), package_json).unwrap();
+        }
+        dir
+    }
+
+    fn commands(processes: Vec<ProcessConfig>) -> Vec<(String, String)> {
+        let mut commands: Vec<(String, String)> = processes
+            .into_iter()
+            .map(|process| (process.name, process.command))
+            .collect();
+        commands.sort();
+        commands
+    }
+
+    #[test]
+    fn guesses_workspace_processes_from_an_array() {
+        let dir = sample_workspace("myterm-workspace-array-test");
+        let package_json = serde_json::json!({ "workspaces": ["packages/*", "./tools/cli"] });
+        assert_eq!(
+            commands(guess_workspace_processes(&dir, &package_json, "npm")),
+            vec![
+                (
+                    "@app/web".to_string(),
+                    "npm run dev -w @app/web".to_string()
+                ),
+                ("api".to_string(), "npm run start -w api".to_string()),
+                ("cli".to_string(), "npm run dev -w cli".to_string()),
+            ]
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn guesses_workspace_processes_from_packages() {
+        let dir = sample_workspace("myterm-workspace-object-test");
+        let package_json = serde_json::json!({
+            "workspaces": { "packages": ["packages/**"], "nohoist": ["**"] }
+        });
+        assert_eq!(
+            commands(guess_workspace_processes(&dir, &package_json, "yarn")),
+            vec![
+                (
+                    "@app/web".to_string(),
+                    "yarn workspace @app/web dev".to_string()
+                ),
+                ("api".to_string(), "yarn workspace api start".to_string()),
+            ]
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    /// Fails every write, like a full disk.
+    struct FullDisk;
+
+    impl Write for FullDisk {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("No space left on device"))
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn log_file_reports_only_the_first_failed_write() {
+        let log_file = ProcessLogFile {
+            path: PathBuf::from("/tmp/web.log"),
+            file: Mutex::new(Some(Box::new(FullDisk))),
+            size: AtomicU64::new(0),
+            max_bytes: None,
+            compress: false,
+            format: LogFormat::Text,
+            prefix_format: None,
+            seq: AtomicU64::new(0),
+        };
+        let err = log_file
+            .write_line(&log_line("/tmp/project", "web", "hello"))
+            .unwrap_err();
+        assert_eq!(err.to_string(), "No space left on device");
+        assert!(log_file.file.lock().unwrap().is_none());
+        assert!(log_file
+            .write_line(&log_line("/tmp/project", "web", "again"))
+            .is_ok());
+    }
+
+    #[test]
+    fn recording_reports_only_the_first_failed_write() {
+        let recording = Recording {
+            path: PathBuf::from("/tmp/web.cast"),
+            started: Instant::now(),
+            file: Mutex::new(Some(Box::new(FullDisk))),
+        };
+        let err = recording.write("o", b"hello").unwrap_err();
+        assert_eq!(err.to_string(), "No space left on device");
+        assert!(recording.write("o", b"again").is_ok());
+        // Already closed by the failure.
+        assert!(!recording.stop());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn log_readers_keep_up_with_a_high_output_child() {
+        const LINES: usize = 20_000;
+        let dir = create_temp_dir("myterm-log-reader-test").unwrap();
+        let log_path = dir.join("loud.log");
+        let manager = ProcessManager::default();
+        let log_file = Arc::new(
+            ProcessLogFile::open(
+                &log_path,
+                &LogFileOptions {
+                    mode: LogFileMode::Truncate,
+                    ..LogFileOptions::default()
+                },
+            )
+            .unwrap(),
+        );
+        let source = || LogSource {
+            log_file: Some(log_file.clone()),
+            ..plain_log_source(manager.clone(), SharedName::new("loud".to_string()))
+        };
+
+        // Far more than a pipe buffer on each stream, written concurrently.
+        let script = format!(
+            "yes 0123456789012345678901234567890123456789 | head -n {n} & \
+             yes abcdefghijklmnopqrstuvwxyzabcdefghijklmn | head -n {n} >&2; wait",
+            n = LINES
+        );
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(script)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("spawn sh");
+        spawn_log_reader(source(), "stdout", child.stdout.take().unwrap());
+        spawn_log_reader(source(), "stderr", child.stderr.take().unwrap());
+
+        let status = wait_for_exit(&mut child).unwrap();
+        assert!(status.success());
+
+        let deadline = Instant::now() + Duration::from_secs(30);
+        let mut written = 0;
+        while Instant::now() < deadline {
+            written = fs::read_to_string(&log_path).unwrap().lines().count();
+            if written == 2 * LINES {
+                break;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        assert_eq!(written, 2 * LINES);
+        let buffered =
+            manager.log_buffers.lock().unwrap()[&process_key("/tmp/project", "loud")].len();
+        assert_eq!(buffered, LOG_BUFFER_LINES);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    fn log_line(project_path: &str, process_name: &str, line: &str) -> LogEvent {
+        LogEvent {
+            project_path: project_path.to_string(),
+            process_name: process_name.to_string(),
+            line: line.to_string(),
+            stream: "stdout".to_string(),
+            raw: None,
+            links: Vec::new(),
+            level: None,
+            color: None,
+            run_id: Some(1),
+            timestamp_ms: 0,
+        }
+    }
+
+    #[test]
+    fn gist_logs_default_to_the_project_buffers() {
+        let manager = ProcessManager::default();
+        {
+            let mut buffers = manager.log_buffers.lock().unwrap();
+            for (project, name, line) in [
+                ("/tmp/app", "web", "listening"),
+                ("/tmp/app", "web", "GET /"),
+                ("/tmp/app", "worker", "idle"),
+                ("/tmp/other", "web", "elsewhere"),
+            ] {
+                buffers
+                    .entry(process_key(project, name))
+                    .or_default()
+                    .push_back(log_line(project, name, line));
+            }
+        }
+        let logs = buffered_project_logs(&manager, "/tmp/app").unwrap();
+        assert_eq!(
+            logs,
+            BTreeMap::from([
+                (
+                    "web".to_string(),
+                    vec!["listening".to_string(), "GET /".to_string()]
+                ),
+                ("worker".to_string(), vec!["idle".to_string()]),
+            ])
+        );
+    }
+
+    #[test]
+    fn log_prefix_format_fills_in_placeholders() {
+        let mut event = log_line("/tmp/project", "api {stream}", "ready");
+        event.stream = "stderr".to_string();
+        event.timestamp_ms = 1_700_000_000_123;
+        let timestamp = chrono::DateTime::from_timestamp_millis(1_700_000_000_123)
+            .unwrap()
+            .with_timezone(&chrono::Local)
+            .to_rfc3339_opts(chrono::SecondsFormat::Millis, false);
+        assert_eq!(
+            format_log_prefix("{timestamp} {process} [{stream}] ", &event),
+            format!("{} api {{stream}} [stderr] ", timestamp)
+        );
+        assert!(timestamp.contains(".123"));
+    }
+
+    #[test]
+    fn log_files_use_the_prefix_format() {
+        let dir = create_temp_dir("myterm-log-prefix-test").unwrap();
+        let plain_path = dir.join("plain.log");
+        let plain = ProcessLogFile::open(&plain_path, &LogFileOptions::default()).unwrap();
+        plain
+            .write_line(&log_line("/tmp/project", "web", "hello"))
+            .unwrap();
+        assert_eq!(fs::read_to_string(&plain_path).unwrap(), "[stdout] hello\n");
+
+        let prefixed_path = dir.join("prefixed.log");
+        let options = LogFileOptions {
+            prefix_format: Some("{process}/{stream}: ".to_string()),
+            ..LogFileOptions::default()
+        };
+        let prefixed = ProcessLogFile::open(&prefixed_path, &options).unwrap();
+        prefixed
+            .write_line(&log_line("/tmp/project", "web", "hello"))
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(&prefixed_path).unwrap(),
+            "web/stdout: hello\n"
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn clipboard_text_keeps_or_strips_colors() {
+        let mut stripped = log_line("/tmp/project", "web", "error: boom");
+        stripped.raw = Some("\u{1b}[31merror\u{1b}[0m: boom".to_string());
+        let colored = log_line("/tmp/project", "web", "\u{1b}[32mok\u{1b}[0m");
+        let events = [stripped, colored];
+        assert_eq!(
+            clipboard_text(&events, false),
+            "\u{1b}[31merror\u{1b}[0m: boom\n\u{1b}[32mok\u{1b}[0m"
+        );
+        assert_eq!(clipboard_text(&events, true), "error: boom\nok");
+        assert_eq!(clipboard_text(&[], true), "");
+    }
+
+    #[test]
+    fn jsonl_log_files_write_numbered_records() {
+        let dir = create_temp_dir("myterm-log-jsonl-test").unwrap();
+        let path = dir.join("web.log");
+        let options = LogFileOptions {
+            format: LogFormat::Jsonl,
+            prefix_format: Some("{process} ".to_string()),
+            ..LogFileOptions::default()
+        };
+        let log_file = ProcessLogFile::open(&path, &options).unwrap();
+        let mut first = log_line("/tmp/project", "web", "say \"hi\"");
+        first.timestamp_ms = 1_700_000_000_000;
+        let mut second = log_line("/tmp/project", "web", "oops");
+        second.stream = "stderr".to_string();
+        second.timestamp_ms = 1_700_000_000_005;
+        log_file.write_line(&first).unwrap();
+        log_file.write_line(&second).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            concat!(
+                r#"{"ts":1700000000000,"process":"web","stream":"stdout","line":"say \"hi\"","seq":0}"#,
+                "\n",
+                r#"{"ts":1700000000005,"process":"web","stream":"stderr","line":"oops","seq":1}"#,
+                "\n",
+            )
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn merged_logs_interleave_processes_by_time() {
+        let manager = ProcessManager::default();
+        {
+            let mut buffers = manager.log_buffers.lock().unwrap();
+            for (project, name, line, at) in [
+                ("/tmp/app", "web", "web 1", 10),
+                ("/tmp/app", "web", "web 2", 30),
+                ("/tmp/app", "web", "web 3", 30),
+                ("/tmp/app", "api", "api 1", 20),
+                ("/tmp/app", "api", "api 2", 40),
+                ("/tmp/app", "worker", "worker 1", 25),
+                ("/tmp/other", "web", "elsewhere", 15),
+            ] {
+                let mut event = log_line(project, name, line);
+                event.timestamp_ms = at;
+                buffers
+                    .entry(process_key(project, name))
+                    .or_default()
+                    .push_back(event);
+            }
+        }
+        let lines = |names: Option<&[String]>, since, until, limit| {
+            merged_logs(&manager, "/tmp/app", names, since, until, limit)
+                .unwrap()
+                .into_iter()
+                .map(|event| event.line)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            lines(None, 0, u128::MAX, 100),
+            ["web 1", "api 1", "worker 1", "web 2", "web 3", "api 2"]
+        );
+        let web_and_api = ["web".to_string(), "api".to_string()];
+        assert_eq!(
+            lines(Some(&web_and_api), 20, 30, 100),
+            ["api 1", "web 2", "web 3"]
+        );
+        assert_eq!(lines(None, 0, u128::MAX, 2), ["web 3", "api 2"]);
+    }
+
+    #[test]
+    fn rotated_log_segments_are_compressed_and_read_back() {
+        let dir = create_temp_dir("myterm-log-rotate-test").unwrap();
+        fs::write(
+            dir.join("myterm.yml"),
+            "name: app\nlog_dir: logs\nlog_max_bytes: 64\nlog_compress: true\nprocesses:\n  - name: web\n    command: npm start\n",
+        )
+        .unwrap();
+        let process = resolve_process_config(&dir, "web").unwrap();
+        let path = process.log_file.clone().unwrap();
+        let log_file = ProcessLogFile::open(&path, &process.log_options).unwrap();
+        // 17 bytes each, so every fourth line starts a new segment.
+        for n in 1..=30 {
+            let line = format!("line {:02}", n);
+            log_file
+                .write_line(&log_line("/tmp/project", "web", &line))
+                .unwrap();
+        }
+
+        let logs = dir.join("logs");
+        for index in 1..=LOG_SEGMENTS_KEPT {
+            assert!(logs.join(format!("web.log.{}.gz", index)).exists());
+            assert!(!logs.join(format!("web.log.{}", index)).exists());
+        }
+        assert!(!logs.join("web.log.6.gz").exists());
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "[stdout] line 29\n[stdout] line 30\n"
+        );
+
+        let project = dir.to_string_lossy().to_string();
+        let history = |limit| {
+            tauri::async_runtime::block_on(get_log_history(
+                project.clone(),
+                "web".to_string(),
+                limit,
+            ))
+            .unwrap()
+        };
+        let kept: Vec<String> = (9..=30)
+            .map(|n| format!("[stdout] line {:02}", n))
+            .collect();
+        assert_eq!(history(None), kept);
+        assert_eq!(history(Some(3)), kept[kept.len() - 3..]);
+        assert_eq!(history(Some(7)), kept[kept.len() - 7..]);
+
+        let found = tauri::async_runtime::block_on(search_logs(
+            project.clone(),
+            "web".to_string(),
+            "LINE 1".to_string(),
+            Some(4),
+        ))
+        .unwrap();
+        assert_eq!(
+            found,
+            ["16", "17", "18", "19"].map(|n| format!("[stdout] line {}", n))
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn attach_monitor_only_removes_its_own_entry() {
+        let manager = ProcessManager::default();
+        let (key, attached_flag) = track(&manager, "web", 4242);
+        // Stopped and replaced by a new process under the same name before the monitor noticed.
+        manager.processes.lock().unwrap().remove(&key);
+        let (_, replacement_flag) = track(&manager, "web", 4343);
+        assert!(!release_attached(&manager, &key, &attached_flag));
+        assert_eq!(manager.processes.lock().unwrap()[&key].pid, 4343);
+
+        assert!(release_attached(&manager, &key, &replacement_flag));
+        assert!(manager.processes.lock().unwrap().is_empty());
+        assert!(!release_attached(&manager, &key, &replacement_flag));
+    }
+
+    #[test]
+    fn containerized_ready_checks_exec_into_the_running_container() {
+        let process = process_from_yaml(
+            "name: api\ncommand: npm start\nready_command: curl -f localhost:3000\nenv:\n  TOKEN: x\ncontainer:\n  image: node:20\n",
+        );
+        let project_path = Path::new("/tmp/shop");
+        let main = plan_spawn("sh", project_path, &process).unwrap();
+        let name = main.container.as_ref().unwrap().name.clone();
+        assert!(main.args[1].contains(&format!("run --rm -i --name '{}'", name)));
+
+        let check = plan_ready_check("sh", project_path, &process)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            check.plan.args[1],
+            format!(
+                "exec docker exec -e 'TOKEN' '{}' sh -c 'curl -f localhost:3000'",
+                name
+            )
+        );
+        assert!(check.plan.container.is_none());
+        assert_eq!(check.plan.env.get("TOKEN").map(String::as_str), Some("x"));
+    }
+
+    #[test]
+    fn processes_to_start_use_the_config_or_its_errors() {
+        let settings = AppSettings::default();
+        let dir = create_temp_dir("myterm-process-to-start-test").unwrap();
+        let project = dir.to_string_lossy().to_string();
+        let start = |name: &str| {
+            process_to_start(
+                &settings,
+                &project,
+                name.to_string(),
+                "npm start".to_string(),
+            )
+        };
+
+        // No config at all: run what the UI sent.
+        let process = start("web").unwrap();
+        assert_eq!(process.command, "npm start");
+        assert!(process.env.is_empty());
+
+        fs::write(
+            dir.join("myterm.yml"),
+            "name: app\nprocesses:\n  - name: web\n    command: npm start\n    env:\n      PORT: \"3000\"\n",
+        )
+        .unwrap();
+        assert_eq!(start("web").unwrap().env["PORT"], "3000");
+        assert!(start("other").unwrap().env.is_empty());
+
+        fs::write(
+            dir.join("myterm.yml"),
+            "name: app\nprocesses:\n  - name: web\n    command: npm start\n    stop_sequence:\n      - signal: TERM\n",
+        )
+        .unwrap();
+        let err = start("web").unwrap_err();
+        assert!(
+            err.starts_with("Process web stop_sequence must end with KILL"),
+            "{}",
+            err
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn readiness_probes_are_killed_at_the_timeout() {
+        let project_path = std::env::temp_dir();
+        let mut process = process_from_yaml(
+            "name: api\ncommand: npm start\nready_command: echo checking; echo warn >&2\nready_timeout_secs: 1\n",
+        );
+        let check = plan_ready_check("sh", &project_path, &process)
+            .unwrap()
+            .unwrap();
+        let result = run_probe(&check);
+        assert!(result.ready);
+        assert_eq!(result.exit_code, Some(0));
+        // The interactive shell may add its own notes on stderr.
+        assert!(result.output.starts_with("checking\n"), "{}", result.output);
+        assert!(result.output.ends_with("warn\n"), "{}", result.output);
+
+        // The background sleep holds the pipes open too, so this only returns if the whole
+        // group is killed.
+        process.ready_command = Some("sleep 30 & sleep 30".to_string());
+        let check = plan_ready_check("sh", &project_path, &process)
+            .unwrap()
+            .unwrap();
+        let started = Instant::now();
+        let result = run_probe(&check);
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(!result.ready);
+        assert_eq!(result.exit_code, None);
+        assert_eq!(result.output, "timed out after 1s");
+    }
+
+    /// A log file that takes a while over every write, so the readers fall behind the child.
+    struct SlowDisk;
+
+    impl Write for SlowDisk {
+        fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+            thread::sleep(Duration::from_millis(150));
+            Ok(bytes.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn runs_log_their_last_lines_before_the_exit() {
+        let mut supervisor = idle_supervisor(Arc::default());
+        supervisor.plan.args = vec!["-c".to_string(), "echo first; echo last".to_string()];
+        supervisor.log_source.log_file = Some(Arc::new(ProcessLogFile {
+            path: PathBuf::from("/tmp/web.log"),
+            file: Mutex::new(Some(Box::new(SlowDisk))),
+            size: AtomicU64::new(0),
+            max_bytes: None,
+            compress: false,
+            format: LogFormat::Text,
+            prefix_format: None,
+            seq: AtomicU64::new(0),
+        }));
+        let status = supervisor.run_child().unwrap().unwrap();
+        assert!(status.success());
+
+        let buffers = supervisor.manager.log_buffers.lock().unwrap();
+        let lines: Vec<&str> = buffers[&process_key("/tmp/project", "web")]
+            .iter()
+            .map(|event| event.line.as_str())
+            .collect();
+        assert_eq!(lines, ["first", "last"]);
+    }
+
+    #[test]
+    fn output_encodings_accept_whatwg_labels() {
+        let process =
+            process_from_yaml("name: legacy\ncommand: ./run\noutput_encoding: Shift_JIS\n");
+        // "日本語" in Shift-JIS.
+        let bytes = [0x93, 0xfa, 0x96, 0x7b, 0x8c, 0xea];
+        assert_eq!(process.output_encoding.decode(&bytes), "日本語");
+        assert_eq!(String::from(process.output_encoding), "shift_jis");
+
+        let latin1 = process_from_yaml("name: legacy\ncommand: ./run\noutput_encoding: latin1\n");
+        assert_eq!(latin1.output_encoding.decode(b"caf\xe9 \x80"), "café €");
+        assert_eq!(OutputEncoding::default().decode(b"ok \xff"), "ok \u{fffd}");
+
+        for label in ["klingon", "utf-16le", "iso-2022-kr"] {
+            let yaml = format!("name: legacy\ncommand: ./run\noutput_encoding: {}\n", label);
+            assert!(
+                serde_yaml::from_str::<ProcessConfig>(&yaml).is_err(),
+                "{}",
+                label
+            );
+        }
+    }
+
+    #[test]
+    fn filtered_subscriptions_honor_min_level() {
+        let subscription = LogSubscription {
+            key: process_key("/tmp/project", "web"),
+            include: None,
+            exclude: Some(Regex::new("healthcheck").unwrap()),
+            min_level: Some(LogLevel::Warn),
+        };
+        let matches = |line: &str| subscription.matches(&log_line("/tmp/project", "web", line));
+        assert!(matches("ERROR database unreachable"));
+        assert!(matches("[warn] slow query"));
+        assert!(!matches("INFO listening on :3000"));
+        assert!(!matches("debug: cache miss"));
+        assert!(!matches("    at handler (server.js:10)"));
+        assert!(!matches("WARN healthcheck failed"));
+
+        // A level detected by `level_colors` is used as is.
+        let mut event = log_line("/tmp/project", "web", "request failed");
+        event.level = Some(LogLevel::Error);
+        assert!(subscription.matches(&event));
+
+        let unfiltered = LogSubscription {
+            min_level: None,
+            ..subscription
+        };
+        assert!(unfiltered.matches(&log_line("/tmp/project", "web", "plain output")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn log_readers_tag_lines_with_their_stream() {
+        let manager = ProcessManager::default();
+        let source = plain_log_source(manager.clone(), SharedName::new("web".to_string()));
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg("echo out; echo err >&2; echo out2")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("spawn sh");
+        let readers = [
+            spawn_log_reader(source.clone(), "stdout", child.stdout.take().unwrap()),
+            spawn_log_reader(source, "stderr", child.stderr.take().unwrap()),
+        ];
+        assert!(wait_for_exit(&mut child).unwrap().success());
+        for reader in readers {
+            reader.join().unwrap();
+        }
+
+        // The two readers race, so only the order within a stream is fixed.
+        let buffers = manager.log_buffers.lock().unwrap();
+        let mut lines: Vec<(&str, &str)> = buffers[&process_key("/tmp/project", "web")]
+            .iter()
+            .map(|event| (event.line.as_str(), event.stream.as_str()))
+            .collect();
+        lines.sort_by_key(|(_, stream)| *stream);
+        assert_eq!(
+            lines,
+            [("err", "stderr"), ("out", "stdout"), ("out2", "stdout")]
+        );
+    }
+}
@@ -2,5 +2,16 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(idx) = args.iter().position(|arg| arg == "--headless") {
+        // `myterm --headless [path]` supervises the project's autostart processes without a window.
+        let config_path = args.get(idx + 1).map(String::as_str).unwrap_or(".");
+        if let Err(err) = myterm_lib::run_headless(std::path::Path::new(config_path)) {
+            eprintln!("myterm: {}", err);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     myterm_lib::run()
 }
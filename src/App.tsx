@@ -13,6 +13,12 @@ type ProcessConfig = {
   command: string;
   autostart?: boolean;
   autorestart?: boolean;
+  disabled?: boolean;
+};
+
+type LaunchProject = {
+  path: string;
+  hasConfig: boolean;
 };
 
 type ActionConfig = {
@@ -45,18 +51,26 @@ type LogEvent = {
   process_name: string;
   line: string;
   stream: string;
+  timestamp_ms: number;
 };
 
 type StatusEvent = {
   project_path: string;
   process_name: string;
   status: ProcessStatus;
+  timestamp_ms: number;
+};
+
+type UpdateProgressEvent = {
+  downloaded_bytes: number;
+  total_bytes: number | null;
 };
 
 type UpdateCheckResult = {
   available: boolean;
   version: string;
   downloadUrl: string;
+  checksumUrl?: string | null;
 };
 
 type ConfigFilePayload = {
@@ -85,6 +99,10 @@ function loadProjectPaths(): string[] {
   }
 }
 
+function formatLogLine(event: LogEvent): string {
+  return event.stream === "stderr" ? `[stderr] ${event.line}` : event.line;
+}
+
 function isMissingConfigError(message: string): boolean {
   const msg = message.toLowerCase();
   if (msg.includes("missing myterm.yml")) return true;
@@ -108,6 +126,8 @@ export default function App() {
   >("idle");
   const [updateVersion, setUpdateVersion] = useState<string | null>(null);
   const [updateDownloadUrl, setUpdateDownloadUrl] = useState<string | null>(null);
+  const [updateChecksumUrl, setUpdateChecksumUrl] = useState<string | null>(null);
+  const [updateProgress, setUpdateProgress] = useState<UpdateProgressEvent | null>(null);
   const [updateNote, setUpdateNote] = useState<string | null>(null);
   const [updateError, setUpdateError] = useState<string | null>(null);
   const [terminalInput, setTerminalInput] = useState("");
@@ -160,11 +180,7 @@ export default function App() {
             ...project,
             processes: project.processes.map((process) => {
               if (process.name !== event.payload.process_name) return process;
-              const line =
-                event.payload.stream === "stderr"
-                  ? `[stderr] ${event.payload.line}`
-                  : event.payload.line;
-              const nextLogs = [...process.logs, line].slice(-500);
+              const nextLogs = [...process.logs, formatLogLine(event.payload)].slice(-500);
               return { ...process, logs: nextLogs };
             }),
           };
@@ -187,9 +203,14 @@ export default function App() {
       );
     });
 
+    const unlistenUpdateProgress = listen<UpdateProgressEvent>("update-progress", (event) => {
+      setUpdateProgress(event.payload);
+    });
+
     return () => {
       unlistenLog.then((fn) => fn());
       unlistenStatus.then((fn) => fn());
+      unlistenUpdateProgress.then((fn) => fn());
     };
   }, []);
 
@@ -212,6 +233,22 @@ export default function App() {
     paths.forEach((path) => {
       loadProjectAtPath(path, false);
     });
+
+    // A project passed on the command line (`myterm ~/code/app`).
+    invoke<LaunchProject | null>("take_launch_project")
+      .then((launch) => {
+        if (launch && !paths.includes(launch.path)) {
+          loadProjectAtPath(launch.path);
+        }
+      })
+      .catch(() => {});
+
+    // Found during startup, before this component could listen for events.
+    invoke<string | null>("take_shell_warning")
+      .then((warning) => {
+        if (warning) setError(warning);
+      })
+      .catch(() => {});
   }, []);
 
   useEffect(() => {
@@ -292,15 +329,25 @@ export default function App() {
 
     try {
       const config = await invoke<ProjectConfig>("load_project_config", { path });
+      // Output buffered by the backend from before this view existed (e.g. after a reload).
+      const backlogs = await Promise.all(
+        config.processes.map((process) =>
+          invoke<LogEvent[]>("get_logs", {
+            projectPath: path,
+            processName: process.name,
+            limit: 500,
+          }).catch(() => [] as LogEvent[])
+        )
+      );
       const project: ProjectView = {
         id: path,
         path,
         name: config.name || path,
         actions: config.actions ?? [],
-        processes: config.processes.map((process) => ({
+        processes: config.processes.map((process, index) => ({
           ...process,
           status: "stopped",
-          logs: [],
+          logs: backlogs[index].map(formatLogLine),
         })),
       };
 
@@ -314,10 +361,10 @@ export default function App() {
       setSelectedProjectId(project.id);
       setSelectedProcessName(project.processes[0]?.name ?? null);
 
-      if (project.processes.some((process) => process.autostart)) {
+      if (project.processes.some((process) => process.autostart && !process.disabled)) {
         setTimeout(() => {
           project.processes
-            .filter((process) => process.autostart)
+            .filter((process) => process.autostart && !process.disabled)
             .forEach((process) => startProcess(project, process));
         }, 0);
       }
@@ -583,11 +630,13 @@ export default function App() {
         setUpdateStatus("available");
         setUpdateVersion(result.version);
         setUpdateDownloadUrl(result.downloadUrl);
+        setUpdateChecksumUrl(result.checksumUrl ?? null);
         setUpdateNote(null);
       } else {
         setUpdateStatus("idle");
         setUpdateVersion(null);
         setUpdateDownloadUrl(null);
+        setUpdateChecksumUrl(null);
         setUpdateNote("You're up to date.");
       }
     } catch (err) {
@@ -599,10 +648,14 @@ export default function App() {
   const handleUpdateNow = async () => {
     if (!updateDownloadUrl) return;
     setUpdateError(null);
+    setUpdateProgress(null);
     setUpdateStatus("downloading");
 
     try {
-      await invoke("install_update", { downloadUrl: updateDownloadUrl });
+      await invoke("install_update", {
+        downloadUrl: updateDownloadUrl,
+        checksumUrl: updateChecksumUrl,
+      });
       setUpdateStatus("restart");
       setUpdateNote(null);
     } catch (err) {
@@ -710,8 +763,35 @@ export default function App() {
               </div>
             )}
 
+            {updateStatus === "available" && !updateChecksumUrl && (
+              <div className="mt-1 text-amber-400">
+                No checksum was published for this release, so the download can't be verified.
+              </div>
+            )}
+
             {updateStatus === "downloading" && (
-              <div className="mt-2 text-slate-500">Downloading update…</div>
+              <div className="mt-2 text-slate-500">
+                Downloading update…
+                {updateProgress &&
+                  (updateProgress.total_bytes
+                    ? ` ${Math.floor(
+                        (updateProgress.downloaded_bytes / updateProgress.total_bytes) * 100
+                      )}%`
+                    : ` ${(updateProgress.downloaded_bytes / (1024 * 1024)).toFixed(1)} MB`)}
+                {updateProgress?.total_bytes ? (
+                  <div className="mt-1 h-1 rounded bg-slate-800">
+                    <div
+                      className="h-1 rounded bg-emerald-500 transition-all"
+                      style={{
+                        width: `${Math.min(
+                          100,
+                          (updateProgress.downloaded_bytes / updateProgress.total_bytes) * 100
+                        )}%`,
+                      }}
+                    />
+                  </div>
+                ) : null}
+              </div>
             )}
 
             {updateStatus === "restart" && (